@@ -2,9 +2,9 @@ use color_eyre::{
     eyre::{eyre, Context},
     Result,
 };
-use tracing::{error, instrument};
+use tracing::{error, info, instrument};
 
-use crate::{package::Package, URL};
+use crate::{package::Package, version_checker::github, URL};
 
 #[instrument]
 pub async fn process_user(username: &str) -> Result<()> {
@@ -22,6 +22,8 @@ pub async fn process_user(username: &str) -> Result<()> {
         }
     }
 
+    report_github_budget();
+
     if should_exit_with_failure {
         return Err(eyre!("Failed to process all packages"));
     }
@@ -37,8 +39,21 @@ pub async fn list_user_packages(username: &str) -> Result<Vec<Package>> {
 #[instrument]
 pub async fn process_package(package_name: &str) -> Result<()> {
     let mut package = Package::new(package_name);
-    package
+    let result = package
         .process()
         .await
-        .wrap_err("failed to process package")
+        .wrap_err("failed to process package");
+    report_github_budget();
+    result
+}
+
+/// Logs how many GitHub API requests are left, if any was made.
+fn report_github_budget() {
+    if let Some(rate_limit) = github::last_rate_limit() {
+        info!(
+            message = "GitHub API budget left",
+            remaining = rate_limit.remaining,
+            limit = rate_limit.limit
+        );
+    }
 }
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::{eyre::WrapErr, Result};
+use serde::Deserialize;
+
+use crate::version_checker::github::DEFAULT_MAX_PAGES;
+use crate::PLUGIN_DIR;
+
+/// Settings read from `config.toml` in the project's configuration directory.
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    /// Hosts running Gitea or Forgejo, their download urls are checked through `/api/v1`.
+    pub gitea_hosts: Vec<String>,
+    /// Self-hosted GitLab instances, their download urls are checked through `/api/v4`.
+    pub gitlab_hosts: Vec<String>,
+    /// Per package settings, keyed by the AUR package name.
+    pub packages: HashMap<String, PackageConfig>,
+    /// Adjustments to the built-in checkers, keyed by checker name.
+    pub checkers: HashMap<String, CheckerConfig>,
+    /// Cross-check new versions against other distributions before updating.
+    pub repology: RepologyConfig,
+    /// Hosts running GitHub Enterprise, keyed by host.
+    pub github_hosts: HashMap<String, GithubHostConfig>,
+    /// Token for the github.com API, the `GITHUB_TOKEN` environment variable takes precedence.
+    /// It is never sent to other hosts.
+    pub github_token: Option<String>,
+    /// Most pages of releases or tags read per repository.
+    pub github_max_pages: usize,
+    /// Directory of external checker executables, named after the host they handle. Defaults to
+    /// `plugins` in the configuration directory.
+    pub plugin_dir: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PackageConfig {
+    /// Name of a built-in checker to use whatever the download url is, e.g. `github`.
+    pub checker: Option<String>,
+    /// Url given to the forced `checker` instead of the download url, e.g. the repository page
+    /// when the download url points at a CDN.
+    pub checker_url: Option<String>,
+    /// Check tags of a plain git remote instead of relying on the download url's domain.
+    pub git: Option<GitConfig>,
+    /// Scrape the links of a web page, like a directory listing.
+    pub web_page: Option<WebPageConfig>,
+    /// Follow the redirects of a stable "latest" url to a versioned download.
+    pub redirect: Option<RedirectConfig>,
+    /// Read the latest version out of a JSON or YAML document.
+    pub endpoint: Option<EndpointConfig>,
+    /// Look for versions in the entries of an Atom or RSS feed.
+    pub feed: Option<FeedConfig>,
+    /// Whether releases marked as prereleases, or with prerelease versions, may be picked.
+    pub prerelease: PrereleasePolicy,
+    /// Release asset to download, matched against the whole asset name. Defaults to the file name
+    /// of the current download url with its version replaced.
+    pub asset: Option<AssetPattern>,
+    /// How versions are read out of tags, for tags like `cli/v1.2.3` or `release-1.2.3`.
+    pub tag: Option<TagMapping>,
+    /// Project name on Repology, defaults to the AUR package name.
+    pub repology_project: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrereleasePolicy {
+    #[default]
+    Never,
+    /// Prereleases compete with stable releases on their version alone.
+    Allow,
+    /// A prerelease is only picked when it is newer than the latest stable release.
+    IfNewerThanStable,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AssetPattern {
+    /// Regex with a `version` named group, e.g. `tool-(?P<version>[0-9.]+)-linux_(amd64|x86_64)`.
+    Regex(String),
+    /// `*` matches any sequence of characters and `{version}` the version, e.g.
+    /// `tool-{version}-linux*.tar.gz`.
+    Glob(String),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TagMapping {
+    /// Stripped from tags, e.g. `cli/`.
+    Prefix(String),
+    /// Regex with a `version` named group, e.g. `release-(?P<version>[0-9.]+)`. Only literal text
+    /// may surround the group so that versions can be turned back into tags.
+    Regex(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct GithubHostConfig {
+    /// API root, defaults to `https://<host>/api/v3`.
+    pub api_url: Option<String>,
+    /// Token only sent to this host.
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CheckerConfig {
+    /// Extra domain globs handled by the checker, e.g. `github.example.org` or `*.example.org`.
+    pub domains: Vec<String>,
+    /// Overrides the checker's priority, the highest one wins when several checkers handle a url.
+    pub priority: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GitConfig {
+    /// Anything `git ls-remote` understands, e.g. `https://git.kernel.org/pub/scm/git/git.git`.
+    pub remote: String,
+    /// Download url with `{version}` and `{tag}` placeholders, defaults to the current download
+    /// url with its version replaced.
+    pub url_template: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct WebPageConfig {
+    /// Page listing the downloads, defaults to the directory of the current download url.
+    pub url: Option<String>,
+    /// Regex matched against each link's file name, it must have a `version` named group.
+    /// Defaults to the file name of the current download url with its version replaced.
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RedirectConfig {
+    /// Url that redirects to the latest download, e.g. `https://example.org/download/latest`.
+    pub url: String,
+    /// Regex matched against the resolved url, it must have a `version` named group. Defaults to
+    /// the file name of the current download url with its version replaced.
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EndpointConfig {
+    pub url: String,
+    /// Detected from the url's extension and the response's content type when missing.
+    pub format: Option<EndpointFormat>,
+    /// Selector for the version, e.g. `$.version` or `$.releases[0].name`.
+    pub version: String,
+    /// Selector for the download url, relative urls are resolved against `url`.
+    pub download_url: Option<String>,
+    /// Used when there is no `download-url` selector, takes `{version}` and `{tag}` placeholders
+    /// and defaults to the current download url with its version replaced.
+    pub url_template: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointFormat {
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FeedConfig {
+    pub url: String,
+    /// Regex matched against entry titles and then links, it must have a `version` named group.
+    /// Defaults to the first version looking text.
+    pub pattern: Option<String>,
+    /// Download url with `{version}` and `{tag}` placeholders, defaults to the current download
+    /// url with its version replaced.
+    pub url_template: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct RepologyConfig {
+    pub mode: RepologyMode,
+    /// Root of a Repology compatible API, defaults to `https://repology.org/api/v1/`.
+    pub base_url: String,
+}
+
+/// What to do when no other repository knows about the version found upstream.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RepologyMode {
+    #[default]
+    Off,
+    Warn,
+    Enforce,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            gitea_hosts: vec!["codeberg.org".to_string()],
+            gitlab_hosts: vec![],
+            github_hosts: HashMap::new(),
+            github_token: None,
+            github_max_pages: DEFAULT_MAX_PAGES,
+            packages: HashMap::new(),
+            checkers: HashMap::new(),
+            repology: RepologyConfig::default(),
+            plugin_dir: None,
+        }
+    }
+}
+
+impl Default for RepologyConfig {
+    fn default() -> Self {
+        Self {
+            mode: RepologyMode::Off,
+            base_url: "https://repology.org/api/v1/".to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Utf8Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read configuration file {:?}", path))?;
+        Self::parse(&contents)
+            .wrap_err_with(|| format!("failed to parse configuration file {:?}", path))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn package(&self, name: &str) -> Option<&PackageConfig> {
+        self.packages.get(name)
+    }
+
+    pub fn checker(&self, name: &str) -> Option<&CheckerConfig> {
+        self.checkers.get(name)
+    }
+
+    /// Name of the package's project on Repology.
+    pub fn repology_project<'a>(&'a self, name: &'a str) -> &'a str {
+        self.package(name)
+            .and_then(|package| package.repology_project.as_deref())
+            .unwrap_or(name)
+    }
+
+    /// API root of a GitHub compatible host. Hosts that are neither github.com nor in
+    /// `github-hosts`, like the ones added to `checkers.github.domains`, get GitHub Enterprise's
+    /// default `/api/v3`.
+    pub fn github_api_url(&self, host: &str) -> String {
+        if host.eq_ignore_ascii_case("github.com") {
+            return "https://api.github.com".to_string();
+        }
+        self.github_host(host)
+            .and_then(|github_host| github_host.api_url.as_deref())
+            .map(|api_url| api_url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| format!("https://{}/api/v3", host))
+    }
+
+    /// Token for the API of `host`, tokens are never shared between hosts.
+    pub fn github_token(&self, host: &str) -> Option<String> {
+        if host.eq_ignore_ascii_case("github.com") {
+            return std::env::var("GITHUB_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty())
+                .or_else(|| self.github_token.clone());
+        }
+        self.github_host(host)?.token.clone()
+    }
+
+    fn github_host(&self, host: &str) -> Option<&GithubHostConfig> {
+        self.github_hosts
+            .iter()
+            .find(|(h, _)| h.eq_ignore_ascii_case(host))
+            .map(|(_, github_host)| github_host)
+    }
+
+    pub fn is_github_host(&self, host: &str) -> bool {
+        host.eq_ignore_ascii_case("github.com") || self.github_host(host).is_some()
+    }
+
+    pub fn plugin_dir(&self) -> &Utf8Path {
+        self.plugin_dir.as_deref().unwrap_or(&PLUGIN_DIR)
+    }
+
+    pub fn is_gitlab_host(&self, host: &str) -> bool {
+        self.gitlab_hosts
+            .iter()
+            .any(|h| h.eq_ignore_ascii_case(host))
+    }
+
+    pub fn is_gitea_host(&self, host: &str) -> bool {
+        self.gitea_hosts
+            .iter()
+            .any(|h| h.eq_ignore_ascii_case(host))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, RepologyMode};
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse("").unwrap();
+        assert!(config.is_gitea_host("codeberg.org"));
+
+        assert!(Config::parse("gitea-hosts = \"git.example.org\"").is_err());
+
+        let config = Config::parse(r#"gitea-hosts = ["git.example.org"]"#).unwrap();
+        assert!(config.is_gitea_host("git.example.org"));
+        assert!(!config.is_gitea_host("codeberg.org"));
+        assert!(!config.is_gitlab_host("git.example.org"));
+
+        let config = Config::parse(r#"gitlab-hosts = ["gitlab.example.org"]"#).unwrap();
+        assert!(config.is_gitlab_host("GitLab.example.org"));
+
+        let config = Config::parse(
+            r#"
+            [packages.git-bin.git]
+            remote = "https://git.kernel.org/pub/scm/git/git.git"
+            "#,
+        )
+        .unwrap();
+        let git = config.package("git-bin").unwrap().git.as_ref().unwrap();
+        assert_eq!(git.remote, "https://git.kernel.org/pub/scm/git/git.git");
+        assert!(git.url_template.is_none());
+        assert!(config.package("other").is_none());
+        assert_eq!(config.repology.mode, RepologyMode::Off);
+        assert_eq!(config.repology_project("git-bin"), "git-bin");
+
+        let config = Config::parse(
+            r#"
+            [repology]
+            mode = "enforce"
+
+            [packages.python-foo]
+            repology-project = "python:foo"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.repology.mode, RepologyMode::Enforce);
+        assert_eq!(config.repology.base_url, "https://repology.org/api/v1/");
+        assert_eq!(config.repology_project("python-foo"), "python:foo");
+
+        let config = Config::parse(
+            r#"
+            github-token = "public"
+
+            [github-hosts."git.corp.example"]
+            api-url = "https://git.corp.example/api/v3/"
+            token = "corporate"
+
+            [github-hosts."git.other.example"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.is_github_host("git.corp.example"));
+        assert!(!config.is_github_host("github.example.org"));
+        assert_eq!(
+            config.github_api_url("GitHub.com"),
+            "https://api.github.com"
+        );
+        assert_eq!(
+            config.github_api_url("git.corp.example"),
+            "https://git.corp.example/api/v3"
+        );
+        assert_eq!(
+            config.github_api_url("github.example.org"),
+            "https://github.example.org/api/v3"
+        );
+        assert_eq!(
+            config.github_api_url("git.other.example"),
+            "https://git.other.example/api/v3"
+        );
+        assert_eq!(
+            config.github_token("git.corp.example").as_deref(),
+            Some("corporate")
+        );
+        assert_eq!(config.github_token("git.other.example"), None);
+        assert_eq!(config.github_token("github.example.org"), None);
+    }
+}
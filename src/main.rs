@@ -1,11 +1,19 @@
 mod commands;
+mod config;
 mod package;
+mod repology;
 mod version;
 mod version_checker;
 
+use std::sync::OnceLock;
+
 use camino::{Utf8Path, Utf8PathBuf};
-use color_eyre::{eyre::WrapErr, Result};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
 use commands::process_package;
+use config::Config;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use reqwest::Client;
@@ -19,12 +27,17 @@ use crate::commands::{list_user_packages, process_user};
 
 const URL: &str = "https://aur.archlinux.org/packages/?K=username&SeB=m";
 
+const USER_AGENT: &str = concat!("AUR-AutoUpdater (+", env!("CARGO_PKG_REPOSITORY"), ")");
+
 lazy_static! {
     pub static ref CLIENT: Client = reqwest::ClientBuilder::new()
-        .user_agent(format!(
-            "AUR-AutoUpdater (+{})",
-            env!("CARGO_PKG_REPOSITORY")
-        ))
+        .user_agent(USER_AGENT)
+        .build()
+        .expect("failed to build request client");
+    /// Client for checkers that need to inspect redirects themselves.
+    pub static ref NO_REDIRECT_CLIENT: Client = reqwest::ClientBuilder::new()
+        .user_agent(USER_AGENT)
+        .redirect(reqwest::redirect::Policy::none())
         .build()
         .expect("failed to build request client");
     static ref PROJECT_DIR: ProjectDirs =
@@ -40,6 +53,20 @@ lazy_static! {
         dir
     };
     pub static ref HELPER_SCRIPT: Utf8PathBuf = CACHE_DIR.join("helper.sh");
+    static ref CONFIG_FILE: Utf8PathBuf = Utf8Path::from_path(PROJECT_DIR.config_dir())
+        .unwrap()
+        .join("config.toml");
+    /// Default directory of the external checker executables.
+    pub static ref PLUGIN_DIR: Utf8PathBuf = Utf8Path::from_path(PROJECT_DIR.config_dir())
+        .unwrap()
+        .join("plugins");
+}
+
+/// Settings loaded by `main`, code running without it, like tests, gets the defaults.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
 }
 
 #[derive(Debug, StructOpt)]
@@ -71,6 +98,9 @@ arg_enum! {
 async fn main() -> Result<()> {
     setup_error_handlers()?;
     let args = Arguments::from_args();
+    CONFIG
+        .set(Config::load(&CONFIG_FILE)?)
+        .map_err(|_| eyre!("configuration was used before being loaded"))?;
     write_helper_script().await?;
 
     match args {
@@ -78,7 +108,7 @@ async fn main() -> Result<()> {
         Arguments::ProcessUser { username } => process_user(&username).await?,
         Arguments::ListUserPackages {
             username,
-            output_type: _,
+            output_type: OutputType::Json,
         } => {
             let packages = list_user_packages(&username)
                 .await
@@ -9,13 +9,15 @@ use futures::StreamExt;
 use lazy_static::lazy_static;
 use scraper::Selector;
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::Deserialize;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 use tokio::{fs, process::Command};
-use tracing::{info, instrument, trace};
+use tracing::{debug, info, instrument, trace};
 
+use crate::repology;
 use crate::version::LenientVersion;
-use crate::{version_checker::get_version_checker, CACHE_DIR, CLIENT, HELPER_SCRIPT};
+use crate::{config, version_checker::get_version_checker, CACHE_DIR, CLIENT, HELPER_SCRIPT};
 
 pub const VERSION_PLACEHOLDER: &str = "_VERSION_PLACEHOLDER_";
 
@@ -115,7 +117,7 @@ impl Package {
     #[instrument(skip(self))]
     async fn cleanup(&self) -> Result<()> {
         let status = Command::new("git")
-            .args(&["remote", "update", "-p"])
+            .args(["remote", "update", "-p"])
             .current_dir(&self.clone_directory)
             .status()
             .await
@@ -129,7 +131,7 @@ impl Package {
         }
 
         let status = Command::new("git")
-            .args(&["reset", "--hard", "origin/master"])
+            .args(["reset", "--hard", "origin/master"])
             .current_dir(&self.clone_directory)
             .status()
             .await
@@ -146,7 +148,7 @@ impl Package {
     async fn parse_pkgbuild(&mut self) -> Result<()> {
         let response = Command::new("bash")
             .arg(&*HELPER_SCRIPT)
-            .arg(&self.pkg_build_file())
+            .arg(self.pkg_build_file())
             .output()
             .await?;
 
@@ -210,33 +212,60 @@ impl Package {
         let mut version_checker = get_version_checker(
             current_download_url,
             self.current_version.as_ref().unwrap().clone(),
+            config(),
+            &self.name,
         )
         .wrap_err("failed to get a version checker")?;
-        version_checker
-            .fetch_last_version(&self.get_file_template()?)
-            .await?;
+        trace!(
+            checker = version_checker.checker_name(),
+            "Found a version checker"
+        );
+        let file_template = self.get_file_template()?;
+        version_checker.fetch_last_version(&file_template).await?;
         if !version_checker.has_newer_version() {
             info!("already on the latest version");
             return Ok(None);
         }
 
+        let remote_version = version_checker.get_remote_version().unwrap();
+        repology::verify(
+            &config().repology,
+            config().repology_project(&self.name),
+            remote_version,
+        )
+        .await?;
+
         let pkg_build_file = self.pkg_build_file();
         let contents = fs::read_to_string(&pkg_build_file).await?;
         let current_version = self.current_version.as_ref().unwrap();
-        let remote_version = version_checker.get_remote_version().unwrap();
         let current_hash = self.current_sha2_digest.as_ref().unwrap().as_str();
-        let remote_hash = calculate_hash(version_checker.get_download_url().unwrap()).await?;
+        let download_url = version_checker.get_download_url().unwrap();
+        verify_file_name(&file_template, download_url, remote_version)?;
+        let digests = calculate_hash(download_url).await?;
+        let remote_hash = digests.sha256;
+        if let Some(expected_hash) = version_checker.get_remote_sha256() {
+            if !expected_hash.eq_ignore_ascii_case(&remote_hash) {
+                return Err(eyre!(
+                    "downloaded file has hash {} but upstream published {}",
+                    remote_hash,
+                    expected_hash
+                ));
+            }
+        }
+        if let Some(integrity) = version_checker.get_remote_integrity() {
+            verify_integrity(integrity, &digests.sha512)?;
+        }
         info!(message = "Updating version", %current_version, %current_hash, %remote_version, %remote_hash);
         let clean = remote_version.clean_original_value();
         let contents = contents
-            .replace(&current_version.original_value(), clean)
-            .replace(&current_hash, &remote_hash);
+            .replace(current_version.original_value(), clean)
+            .replace(current_hash, &remote_hash);
 
         trace!(message = "Final PKGBUILD file", %contents);
         fs::write(&pkg_build_file, contents).await?;
 
         let response = Command::new("makepkg")
-            .args(&["--force", "--syncdeps", "--noconfirm"])
+            .args(["--force", "--syncdeps", "--noconfirm"])
             // Some variables can interfer with packages like coveralls with git commiter name
             .env_clear()
             .env("PATH", env::var("PATH").unwrap_or_default())
@@ -258,27 +287,30 @@ impl Package {
     fn get_file_template(&self) -> Result<String> {
         let current_download_url = self.current_download_url.as_ref().unwrap();
         let parsed_url = url::Url::parse(current_download_url.as_str())?;
-        let segments = parsed_url
+        let mut segments = parsed_url
             .path_segments()
             .ok_or_else(|| eyre!("Could not determine the download file"))?;
         let file_name = segments
-            .last()
+            .next_back()
             .ok_or_else(|| eyre!("Could not determine the download file"))?;
 
-        Ok(file_name.replace(
-            self.current_version.as_ref().unwrap().to_string().as_str(),
-            VERSION_PLACEHOLDER,
-        ))
+        let current_version = self.current_version.as_ref().unwrap();
+        let normalized_version = current_version.to_string();
+        // Short versions like `2.10` are displayed as `2.10.0`, so fall back to the PKGBUILD value
+        let version = if file_name.contains(&normalized_version) {
+            normalized_version.as_str()
+        } else {
+            current_version.original_value()
+        };
+
+        Ok(file_name.replace(version, VERSION_PLACEHOLDER))
     }
 
     #[instrument(skip(self))]
     async fn write_src_info(&self) -> Result<()> {
         let response = Command::new("bash")
             .arg("-exc")
-            .arg(&format!(
-                "makepkg --printsrcinfo > {}",
-                self.src_info_file()
-            ))
+            .arg(format!("makepkg --printsrcinfo > {}", self.src_info_file()))
             .current_dir(&self.clone_directory)
             .spawn()?
             .wait()
@@ -294,8 +326,8 @@ impl Package {
     #[instrument(skip(self))]
     async fn commit(&self, version: &str) -> Result<()> {
         let response = Command::new("git")
-            .args(&["commit", "-am"])
-            .arg(&format!("Update to version {}", version))
+            .args(["commit", "-am"])
+            .arg(format!("Update to version {}", version))
             .current_dir(&self.clone_directory)
             .spawn()?
             .wait()
@@ -321,18 +353,86 @@ impl Package {
     }
 }
 
+struct Digests {
+    /// Hex encoded, as written in PKGBUILDs.
+    sha256: String,
+    /// Base64 encoded, as written in subresource integrity strings.
+    sha512: String,
+}
+
 #[instrument]
-async fn calculate_hash(url: &str) -> Result<String> {
+async fn calculate_hash(url: &str) -> Result<Digests> {
     info!("Calculating hash for the downloaded URL");
     let mut hash = Sha256::default();
+    let mut sha512 = Sha512::default();
     let mut stream = CLIENT.get(url).send().await?.bytes_stream();
     while let Some(chunk) = stream.next().await {
-        hash.update(chunk?);
+        let chunk = chunk?;
+        hash.update(&chunk);
+        sha512.update(&chunk);
     }
 
-    let final_hash = format!("{:x}", hash.finalize());
+    let final_hash = hash
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
     info!(message = "Done", %final_hash);
-    Ok(final_hash)
+    Ok(Digests {
+        sha256: final_hash,
+        sha512: BASE64.encode(sha512.finalize()),
+    })
+}
+
+/// Only the version and the hash of the PKGBUILD are updated, so the new download has to follow
+/// the file name of the current one.
+fn verify_file_name(
+    file_template: &str,
+    download_url: &str,
+    version: &LenientVersion,
+) -> Result<()> {
+    let url = url::Url::parse(download_url)?;
+    let file_name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default();
+    let expected = file_template.replace(VERSION_PLACEHOLDER, version.clean_original_value());
+    let version_forms = [version.original_value(), &version.to_string()];
+    if file_name == expected
+        || version_forms
+            .iter()
+            .any(|form| file_name == file_template.replace(VERSION_PLACEHOLDER, form))
+    {
+        return Ok(());
+    }
+    Err(eyre!(
+        "new download {} is named {:?} instead of {:?}, the PKGBUILD source has to be updated by hand",
+        download_url,
+        file_name,
+        expected
+    ))
+}
+
+/// Checks the `sha512` hashes of a subresource integrity string against the download, other
+/// algorithms are ignored.
+fn verify_integrity(integrity: &str, sha512: &str) -> Result<()> {
+    let expected: Vec<&str> = integrity
+        .split_whitespace()
+        .filter_map(|hash| hash.strip_prefix("sha512-"))
+        .map(|hash| hash.split('?').next().unwrap_or(hash))
+        .collect();
+    if expected.is_empty() {
+        debug!("no sha512 hash in integrity {:?}", integrity);
+        return Ok(());
+    }
+    if !expected.contains(&sha512) {
+        return Err(eyre!(
+            "downloaded file has integrity sha512-{} but upstream published {}",
+            sha512,
+            integrity
+        ));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -346,7 +446,10 @@ mod tests {
 
     use crate::{setup_error_handlers, write_helper_script};
 
-    use super::{calculate_hash, Package, CACHE_DIR};
+    use super::{
+        calculate_hash, verify_file_name, verify_integrity, Package, CACHE_DIR, VERSION_PLACEHOLDER,
+    };
+    use crate::version::LenientVersion;
 
     const TEST_PACKAGE: &[u8] = include_bytes!("../tests/fixtures/test-package.tar.gz");
 
@@ -364,7 +467,7 @@ mod tests {
     async fn setup_test_repository() -> TempDir {
         let remote_repository = TempDir::new("aur-autoupdater").unwrap();
         let response = Command::new("git")
-            .args(&["init", "--bare"])
+            .args(["init", "--bare"])
             .arg(remote_repository.as_ref())
             .status()
             .await
@@ -418,6 +521,7 @@ mod tests {
     #[tokio::test]
     async fn test_calculate_hash() {
         let expected_hash = "1d38233b764e0ac9f326cbd06474b6454cf80ccb3c6d0b44b7697a8f51e5891e";
+        let expected_sha512 = "+oHvjU7DRW3kWaqj3wVbqekziOjpurJ4UY8+fdqFZxdrPjAEwPY5gyMmZOGqUiHjVf3DfySVB5q0y0vCYMdsHA==";
 
         let mock_server = MockServer::start().await;
         Mock::given(method("GET"))
@@ -428,11 +532,51 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        assert_eq!(
-            expected_hash,
-            calculate_hash(&format!("{}/test-package.tar.gz", mock_server.uri()))
-                .await
-                .unwrap()
+        let digests = calculate_hash(&format!("{}/test-package.tar.gz", mock_server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(expected_hash, digests.sha256);
+        assert_eq!(expected_sha512, digests.sha512);
+    }
+
+    #[test]
+    fn test_verify_integrity() {
+        let sha512 = "+oHvjU7DRW3kWaqj3wVbqekziOjpurJ4UY8+fdqFZxdrPjAEwPY5gyMmZOGqUiHjVf3DfySVB5q0y0vCYMdsHA==";
+        verify_integrity(&format!("sha512-{}", sha512), sha512).unwrap();
+        verify_integrity(&format!("sha1-abc sha512-other sha512-{}", sha512), sha512).unwrap();
+        verify_integrity("sha1-abc", sha512).unwrap();
+        assert!(verify_integrity("sha512-other", sha512).is_err());
+    }
+
+    #[test]
+    fn test_verify_file_name() {
+        let version = LenientVersion::parse("3.1").unwrap();
+        let file_template = format!("ConfigUpdater-{}.tar.gz", VERSION_PLACEHOLDER);
+        verify_file_name(
+            &file_template,
+            "https://files.pythonhosted.org/packages/ab/cd/ConfigUpdater-3.1.tar.gz",
+            &version,
+        )
+        .unwrap();
+        verify_file_name(
+            &file_template,
+            "https://files.pythonhosted.org/packages/ab/cd/ConfigUpdater-3.1.0.tar.gz",
+            &version,
+        )
+        .unwrap();
+        verify_file_name(
+            &format!("tool-{}.tar.gz", VERSION_PLACEHOLDER),
+            "https://example.org/tool-v2.tar.gz",
+            &LenientVersion::parse("v2").unwrap(),
+        )
+        .unwrap();
+        let error = verify_file_name(
+            &file_template,
+            "https://files.pythonhosted.org/packages/ab/cd/configupdater-3.1.zip",
+            &version,
         )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("\"configupdater-3.1.zip\" instead of \"ConfigUpdater-3.1.tar.gz\""));
     }
 }
@@ -0,0 +1,159 @@
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::Deserialize;
+use tracing::{instrument, trace, warn};
+use url::Url;
+
+use crate::config::{RepologyConfig, RepologyMode};
+use crate::version::LenientVersion;
+use crate::CLIENT;
+
+/// Repository name of the AUR on Repology, it is skipped as it is the one being updated.
+const AUR_REPOSITORY: &str = "aur";
+
+#[derive(Debug, Deserialize)]
+struct ProjectPackage {
+    repo: String,
+    version: String,
+}
+
+/// Makes sure some other repository already ships `version`, guarding against bogus upstream
+/// tags. Depending on the configured mode a miss is ignored, logged or turned into an error.
+#[instrument(skip(config))]
+pub async fn verify(
+    config: &RepologyConfig,
+    project: &str,
+    version: &LenientVersion,
+) -> Result<()> {
+    if config.mode == RepologyMode::Off {
+        return Ok(());
+    }
+
+    let known = match is_known_version(config, project, version).await {
+        Ok(known) => known,
+        Err(e) if config.mode == RepologyMode::Warn => {
+            warn!("failed to cross-check version with repology: {:?}", e);
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+    if known {
+        return Ok(());
+    }
+
+    let message = format!(
+        "version {} of {} is not known by any other repository",
+        version.original_value(),
+        project
+    );
+    match config.mode {
+        RepologyMode::Enforce => Err(eyre!(message)),
+        _ => {
+            warn!("{}", message);
+            Ok(())
+        }
+    }
+}
+
+async fn is_known_version(
+    config: &RepologyConfig,
+    project: &str,
+    version: &LenientVersion,
+) -> Result<bool> {
+    let url = Url::parse(&config.base_url)
+        .and_then(|base_url| base_url.join(&format!("project/{}", project)))
+        .wrap_err_with(|| format!("invalid repology url {:?}", config.base_url))?;
+    trace!(message = "fetching repology project", url = %url);
+    let packages: Vec<ProjectPackage> = CLIENT
+        .get(url)
+        .send()
+        .await
+        .wrap_err("failed to get repology project")?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(packages
+        .iter()
+        .filter(|package| package.repo != AUR_REPOSITORY)
+        .any(|package| {
+            package.version == version.clean_original_value()
+                || LenientVersion::parse(&package.version).is_ok_and(|v| &v == version)
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::verify;
+    use crate::config::{RepologyConfig, RepologyMode};
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+
+    #[tokio::test]
+    async fn test_verify() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/project/tool"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"repo": "aur", "version": "99.0"},
+                {"repo": "arch", "version": "1.4.0"},
+                {"repo": "nix_unstable", "version": "1.5.0"}
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = RepologyConfig {
+            mode: RepologyMode::Enforce,
+            base_url: format!("{}/api/v1/", mock_server.uri()),
+        };
+        verify(&config, "tool", &LenientVersion::parse("v1.5.0").unwrap())
+            .await
+            .unwrap();
+        assert!(
+            verify(&config, "tool", &LenientVersion::parse("v99.0").unwrap())
+                .await
+                .is_err()
+        );
+
+        config.mode = RepologyMode::Warn;
+        verify(&config, "tool", &LenientVersion::parse("v99.0").unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_single_digit_versions() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/project/app"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"repo": "arch", "version": "9"}
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = RepologyConfig {
+            mode: RepologyMode::Enforce,
+            base_url: format!("{}/api/v1/", mock_server.uri()),
+        };
+        verify(&config, "app", &LenientVersion::parse("9").unwrap())
+            .await
+            .unwrap();
+        assert!(verify(&config, "app", &LenientVersion::parse("8").unwrap())
+            .await
+            .is_err());
+
+        config.mode = RepologyMode::Warn;
+        verify(&config, "app", &LenientVersion::parse("8").unwrap())
+            .await
+            .unwrap();
+    }
+}
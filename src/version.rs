@@ -10,7 +10,7 @@ use serde::{Deserialize, Deserializer};
 pub struct LenientVersion(Version, String);
 
 impl LenientVersion {
-    pub fn parse(v: &str) -> Result<Self, lenient_semver::parser::Error> {
+    pub fn parse(v: &str) -> Result<Self, lenient_semver::parser::Error<'_>> {
         lenient_semver::parse(v).map(|parsed| Self(parsed, v.to_string()))
     }
     pub fn inner(&self) -> &Version {
@@ -22,10 +22,10 @@ impl LenientVersion {
     }
 
     pub fn clean_original_value(&self) -> &str {
-        match self.1.as_bytes()[..2] {
-            [b'v', b'0'..=b'9'] => &self.1[1..],
-            _ => &self.1,
-        }
+        self.1
+            .strip_prefix('v')
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(&self.1)
     }
 }
 
@@ -45,7 +45,7 @@ impl Eq for LenientVersion {}
 
 impl PartialOrd for LenientVersion {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        PartialOrd::partial_cmp(&self.0, &other.0)
+        Some(self.cmp(other))
     }
 }
 
@@ -99,5 +99,9 @@ mod tests {
                 LenientVersion::parse(value).unwrap().clean_original_value()
             );
         }
+        assert_eq!(
+            "9",
+            LenientVersion::parse("9").unwrap().clean_original_value()
+        );
     }
 }
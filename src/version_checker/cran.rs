@@ -0,0 +1,166 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use tracing::trace;
+use url::Url;
+
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://cran.r-project.org/"
+        .parse()
+        .expect("error parsing cran url");
+}
+
+pub struct Cran {
+    base_url: Url,
+    current_download_url: Url,
+    current_version: LenientVersion,
+    package_name: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl Cran {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        Cran::with_cran_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_cran_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Packages are downloaded from /src/contrib/<name>_<version>.tar.gz, or from
+        // /src/contrib/Archive/<name>/ once superseded
+        let package_name = current_download_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|file_name| file_name.rsplit_once('_'))
+            .map(|(package_name, _)| package_name)
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get package from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_download_url: current_download_url.clone(),
+            current_version,
+            package_name: package_name.to_string(),
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("web/packages/{}/DESCRIPTION", self.package_name))
+            .wrap_err("failed to get cran url")?;
+        trace!(message = "fetching cran version", file_template = file_template, url = %url);
+        let description = CLIENT
+            .get(url)
+            .send()
+            .await
+            .wrap_err("failed to get latest version")?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let number = description
+            .lines()
+            .find_map(|line| line.strip_prefix("Version:"))
+            .map(str::trim)
+            .ok_or_else(|| eyre!("no version in description of {}", self.package_name))?;
+        // R versions like 1.1-3 are packaged as 1.1.3
+        let normalised = number.replace('-', ".");
+        let version = LenientVersion::parse(&normalised)
+            .map_err(|e| eyre!("failed to parse version {:?} {:?}", number, e))?;
+        let download_url = self
+            .current_download_url
+            .join(&format!(
+                "/src/contrib/{}_{}.tar.gz",
+                self.package_name, number
+            ))
+            .wrap_err("failed to build download url")?;
+        self.remote_version = Some(version);
+        self.remote_url = Some(download_url.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for Cran {
+    fn checker_name(&self) -> &'static str {
+        "cran"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::cran::Cran;
+    use crate::version_checker::VersionCheck;
+
+    const DESCRIPTION: &str = "Package: sp
+Version: 2.0-1
+Title: Classes and Methods for Spatial Data
+Depends: R (>= 3.5.0), methods
+";
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/web/packages/sp/DESCRIPTION"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(DESCRIPTION))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut cran: Box<dyn VersionCheck> = Box::new(
+            Cran::with_cran_url(
+                &format!("{}/", mock_server.uri()).parse().unwrap(),
+                &"https://cran.r-project.org/src/contrib/Archive/sp/sp_1.6-1.tar.gz"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("1.6.1").unwrap(),
+            )
+            .unwrap(),
+        );
+        cran.fetch_last_version("sp_1.6-1.tar.gz").await.unwrap();
+        mock_server.verify().await;
+
+        assert!(cran.has_newer_version());
+        assert_eq!(cran.get_remote_version().unwrap().original_value(), "2.0.1");
+        assert_eq!(
+            cran.get_download_url(),
+            Some("https://cran.r-project.org/src/contrib/sp_2.0-1.tar.gz")
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tracing::trace;
+use url::Url;
+
+use crate::package::VERSION_PLACEHOLDER;
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://crates.io/api/v1/"
+        .parse()
+        .expect("error parsing crates.io url");
+}
+
+pub struct CratesIo {
+    base_url: Url,
+    current_download_url: Url,
+    current_version: LenientVersion,
+    crate_name: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Crate {
+    versions: Vec<Version>,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    num: String,
+    yanked: bool,
+}
+
+impl CratesIo {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        CratesIo::with_crates_io_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_crates_io_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Both https://static.crates.io/crates/<name>/<file> and
+        // https://crates.io/api/v1/crates/<name>/<version>/download are supported
+        let crate_name = current_download_url
+            .path_segments()
+            .and_then(|mut segments| {
+                segments
+                    .by_ref()
+                    .find(|segment| *segment == "crates")
+                    .and_then(|_| segments.next())
+            })
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get crate from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_download_url: current_download_url.clone(),
+            current_version,
+            crate_name: crate_name.to_string(),
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("crates/{}", self.crate_name))
+            .wrap_err("failed to get crates.io url")?;
+        trace!(message = "fetching crates.io version", file_template = file_template, url = %url);
+        let response = CLIENT
+            .get(url)
+            .send()
+            .await
+            .wrap_err("failed to get latest version")?
+            .error_for_status()?;
+        let krate: Crate = response.json().await?;
+
+        if let Some(version) = krate.latest_version() {
+            self.remote_url = Some(self.get_download_url_for(file_template, &version)?);
+            self.remote_version = Some(version);
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the current download url for `version`, the file name comes from `file_template`
+    /// and any other path segment holding the current version is replaced as well.
+    fn get_download_url_for(
+        &self,
+        file_template: &str,
+        version: &LenientVersion,
+    ) -> Result<String> {
+        let directory = self
+            .current_download_url
+            .join(".")
+            .wrap_err("failed to get the download directory")?;
+        let url_template = format!(
+            "{}{}",
+            directory
+                .as_str()
+                .replace(&self.current_version.to_string(), VERSION_PLACEHOLDER),
+            file_template
+        );
+        Ok(url_template.replace(VERSION_PLACEHOLDER, version.original_value()))
+    }
+}
+
+impl Crate {
+    fn latest_version(&self) -> Option<LenientVersion> {
+        self.versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| LenientVersion::parse(&v.num).ok())
+            .filter(|v| v.inner().pre.is_empty())
+            .max()
+    }
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for CratesIo {
+    fn checker_name(&self) -> &'static str {
+        "crates-io"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::package::VERSION_PLACEHOLDER;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::crates_io::CratesIo;
+    use crate::version_checker::VersionCheck;
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/crates/mambembe-cli"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "versions": [
+                    {"num": "0.3.0-beta.1", "yanked": false},
+                    {"num": "0.2.1", "yanked": true},
+                    {"num": "0.2.0", "yanked": false},
+                    {"num": "0.1.0", "yanked": false}
+                ]
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let base_url = format!("{}/api/v1/", mock_server.uri()).parse().unwrap();
+        let current_version = LenientVersion::parse("0.1.0").unwrap();
+        let cases = [
+            (
+                "https://static.crates.io/crates/mambembe-cli/mambembe-cli-0.1.0.crate",
+                format!("mambembe-cli-{}.crate", VERSION_PLACEHOLDER),
+                "https://static.crates.io/crates/mambembe-cli/mambembe-cli-0.2.0.crate",
+            ),
+            (
+                "https://crates.io/api/v1/crates/mambembe-cli/0.1.0/download",
+                "download".to_string(),
+                "https://crates.io/api/v1/crates/mambembe-cli/0.2.0/download",
+            ),
+        ];
+
+        for (download_url, file_template, expected_url) in cases {
+            let mut crates_io: Box<dyn VersionCheck> = Box::new(
+                CratesIo::with_crates_io_url(
+                    &base_url,
+                    &download_url.parse().unwrap(),
+                    current_version.clone(),
+                )
+                .unwrap(),
+            );
+            crates_io.fetch_last_version(&file_template).await.unwrap();
+
+            assert!(crates_io.has_newer_version());
+            assert_eq!(
+                crates_io.get_remote_version(),
+                Some(&LenientVersion::parse("0.2.0").unwrap())
+            );
+            assert_eq!(crates_io.get_download_url(), Some(expected_url));
+        }
+        mock_server.verify().await;
+    }
+}
@@ -0,0 +1,364 @@
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use reqwest::header::CONTENT_TYPE;
+use serde_json::Value;
+use tracing::{instrument, trace};
+use url::Url;
+
+use super::tag_mapping::TagMapper;
+use super::{default_url_template, expand_url_template, VersionCheck};
+use crate::config::{EndpointConfig, EndpointFormat};
+use crate::version::LenientVersion;
+use crate::CLIENT;
+
+/// Checker for vendors exposing their latest release in a JSON or YAML document, like the
+/// `latest-linux.yml` files written by electron-builder.
+#[derive(Debug)]
+pub struct Endpoint {
+    url: Url,
+    format: Option<EndpointFormat>,
+    version_selector: Selector,
+    download_url_selector: Option<Selector>,
+    url_template: String,
+    tags: TagMapper,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl Endpoint {
+    pub fn new(
+        current_download_url: &str,
+        current_version: LenientVersion,
+        config: &EndpointConfig,
+    ) -> Result<Self> {
+        let url = config
+            .url
+            .parse()
+            .wrap_err_with(|| format!("failed to parse endpoint url {:?}", config.url))?;
+        let url_template = config
+            .url_template
+            .clone()
+            .unwrap_or_else(|| default_url_template(current_download_url, &current_version));
+
+        Ok(Self {
+            url,
+            format: config.format,
+            version_selector: Selector::parse(&config.version)?,
+            download_url_selector: config
+                .download_url
+                .as_deref()
+                .map(Selector::parse)
+                .transpose()?,
+            url_template,
+            tags: TagMapper::default(),
+            current_version,
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    /// Reads versions out of tags, and back for the `{tag}` placeholder.
+    pub fn with_tags(mut self, tags: TagMapper) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self) -> Result<()> {
+        let response = CLIENT
+            .get(self.url.clone())
+            .send()
+            .await
+            .wrap_err("failed to load endpoint")?
+            .error_for_status()?;
+        let format = match self.format {
+            Some(format) => format,
+            None => detect_format(&self.url, response.headers().get(CONTENT_TYPE)),
+        };
+        let body = response
+            .text()
+            .await
+            .wrap_err("failed to get the content of endpoint")?;
+        trace!(message = "fetched endpoint", ?format, %body);
+        let document: Value = match format {
+            EndpointFormat::Json => serde_json::from_str(&body)?,
+            EndpointFormat::Yaml => serde_norway::from_str(&body)?,
+        };
+
+        let version = self.version_selector.select_string(&document)?;
+        let version = self
+            .tags
+            .version(&version)
+            .ok_or_else(|| eyre!("failed to parse version {:?}", version))?;
+        let download_url = match &self.download_url_selector {
+            Some(selector) => {
+                let download_url = selector.select_string(&document)?;
+                // electron-builder only writes the file name, relative to the document
+                self.url
+                    .join(&download_url)
+                    .wrap_err_with(|| format!("invalid download url {:?}", download_url))?
+                    .to_string()
+            }
+            None => expand_url_template(&self.url_template, &version, &self.tags),
+        };
+
+        self.remote_version = Some(version);
+        self.remote_url = Some(download_url);
+        Ok(())
+    }
+}
+
+fn detect_format(url: &Url, content_type: Option<&reqwest::header::HeaderValue>) -> EndpointFormat {
+    let content_type = content_type
+        .and_then(|c| c.to_str().ok())
+        .unwrap_or_default();
+    let path = url.path();
+    if content_type.contains("yaml") || path.ends_with(".yml") || path.ends_with(".yaml") {
+        EndpointFormat::Yaml
+    } else {
+        EndpointFormat::Json
+    }
+}
+
+/// JSONPath-like selector supporting `$.key.other[0]["key.with.dots"]`, the leading `$` is
+/// optional.
+#[derive(Debug, PartialEq)]
+struct Selector(Vec<Segment>);
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+impl Selector {
+    fn parse(selector: &str) -> Result<Self> {
+        let invalid = || eyre!("invalid selector {:?}", selector);
+        let mut segments = vec![];
+        let mut rest = selector.strip_prefix('$').unwrap_or(selector);
+
+        while !rest.is_empty() {
+            if let Some(bracketed) = rest.strip_prefix('[') {
+                let end = bracketed.find(']').ok_or_else(invalid)?;
+                let inner = &bracketed[..end];
+                let quoted = inner
+                    .strip_prefix('"')
+                    .and_then(|i| i.strip_suffix('"'))
+                    .or_else(|| inner.strip_prefix('\'').and_then(|i| i.strip_suffix('\'')));
+                segments.push(match quoted {
+                    Some(key) => Segment::Key(key.to_string()),
+                    None => Segment::Index(inner.parse().map_err(|_| invalid())?),
+                });
+                rest = &bracketed[end + 1..];
+            } else {
+                let key = rest.strip_prefix('.').unwrap_or(rest);
+                let end = key.find(['.', '[']).unwrap_or(key.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                segments.push(Segment::Key(key[..end].to_string()));
+                rest = &key[end..];
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self(segments))
+    }
+
+    fn select<'a>(&self, document: &'a Value) -> Option<&'a Value> {
+        self.0
+            .iter()
+            .try_fold(document, |value, segment| match segment {
+                Segment::Key(key) => value.get(key),
+                Segment::Index(index) => value.get(index),
+            })
+    }
+
+    fn select_string(&self, document: &Value) -> Result<String> {
+        match self.select(document) {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(Value::Number(n)) => Ok(n.to_string()),
+            Some(other) => Err(eyre!(
+                "selector {:?} matched {} which is not text",
+                self,
+                other
+            )),
+            None => Err(eyre!("selector {:?} did not match anything", self)),
+        }
+    }
+}
+
+#[async_trait]
+impl VersionCheck for Endpoint {
+    fn checker_name(&self) -> &'static str {
+        "endpoint"
+    }
+
+    async fn fetch_last_version(&mut self, _file_template: &str) -> Result<()> {
+        self.do_fetch_last_version().await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{Endpoint, Segment, Selector, VersionCheck};
+    use crate::config::EndpointConfig;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+
+    const LATEST_YML: &str = "version: 1.4.2
+files:
+  - url: App-1.4.2.AppImage
+    sha512: abc
+path: App-1.4.2.AppImage
+releaseDate: '2023-05-01T10:00:00.000Z'
+";
+
+    #[test]
+    fn parse_selector() {
+        assert_eq!(
+            Selector::parse(r#"$.files[0]["url.x"]"#).unwrap(),
+            Selector(vec![
+                Segment::Key("files".to_string()),
+                Segment::Index(0),
+                Segment::Key("url.x".to_string())
+            ])
+        );
+        assert_eq!(
+            Selector::parse("version").unwrap(),
+            Selector(vec![Segment::Key("version".to_string())])
+        );
+        assert!(Selector::parse("$").is_err());
+        assert!(Selector::parse("files[first]").is_err());
+    }
+
+    #[tokio::test]
+    async fn fetch_last_version_from_yaml() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/updates/latest-linux.yml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(LATEST_YML))
+            .mount(&mock_server)
+            .await;
+
+        let config = EndpointConfig {
+            url: format!("{}/updates/latest-linux.yml", mock_server.uri()),
+            format: None,
+            version: "version".to_string(),
+            download_url: Some("$.files[0].url".to_string()),
+            url_template: None,
+        };
+        let mut endpoint: Box<dyn VersionCheck> = Box::new(
+            Endpoint::new(
+                "https://example.org/updates/App-1.4.0.AppImage",
+                LenientVersion::parse("1.4.0").unwrap(),
+                &config,
+            )
+            .unwrap(),
+        );
+        endpoint
+            .fetch_last_version("App-1.4.0.AppImage")
+            .await
+            .unwrap();
+
+        assert!(endpoint.has_newer_version());
+        assert_eq!(
+            endpoint.get_download_url(),
+            Some(format!("{}/updates/App-1.4.2.AppImage", mock_server.uri()).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_last_version_from_json() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/products"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "IIU": [{"version": "2023.1.2", "downloads": {}}]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = EndpointConfig {
+            url: format!("{}/products", mock_server.uri()),
+            format: None,
+            version: "$.IIU[0].version".to_string(),
+            download_url: None,
+            url_template: None,
+        };
+        let mut endpoint = Endpoint::new(
+            "https://download.example.org/idea/ideaIU-2023.1.1.tar.gz",
+            LenientVersion::parse("2023.1.1").unwrap(),
+            &config,
+        )
+        .unwrap();
+        endpoint
+            .fetch_last_version("ideaIU-2023.1.1.tar.gz")
+            .await
+            .unwrap();
+
+        assert!(endpoint.has_newer_version());
+        assert_eq!(
+            endpoint.get_download_url(),
+            Some("https://download.example.org/idea/ideaIU-2023.1.2.tar.gz")
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_last_version_from_single_digit_versions() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/latest.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"version": 10})))
+            .mount(&mock_server)
+            .await;
+
+        let config = EndpointConfig {
+            url: format!("{}/latest.json", mock_server.uri()),
+            format: None,
+            version: "version".to_string(),
+            download_url: None,
+            url_template: None,
+        };
+        let mut endpoint = Endpoint::new(
+            "https://download.example.org/app9/app-9.tar.gz",
+            LenientVersion::parse("9").unwrap(),
+            &config,
+        )
+        .unwrap();
+        endpoint.fetch_last_version("app-9.tar.gz").await.unwrap();
+
+        assert!(endpoint.has_newer_version());
+        assert_eq!(
+            endpoint.get_download_url(),
+            Some("https://download.example.org/app9/app-10.tar.gz")
+        );
+    }
+}
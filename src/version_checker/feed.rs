@@ -0,0 +1,255 @@
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use tracing::{debug, instrument};
+use url::Url;
+
+use super::tag_mapping::TagMapper;
+use super::{default_url_template, expand_url_template, version_pattern, VersionCheck};
+use crate::config::FeedConfig;
+use crate::version::LenientVersion;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref DEFAULT_PATTERN: Regex =
+        Regex::new(r"(?:^|[^0-9A-Za-z])v?(?P<version>[0-9]+(?:\.[0-9]+)+(?:-[0-9A-Za-z.]+)?)")
+            .unwrap();
+}
+
+/// Checker for upstreams announcing releases through Atom or RSS feeds, like GitHub's
+/// `releases.atom`.
+#[derive(Debug)]
+pub struct Feed {
+    url: Url,
+    pattern: Regex,
+    url_template: String,
+    tags: TagMapper,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl Feed {
+    pub fn new(
+        current_download_url: &str,
+        current_version: LenientVersion,
+        config: &FeedConfig,
+    ) -> Result<Self> {
+        let url = config
+            .url
+            .parse()
+            .wrap_err_with(|| format!("failed to parse feed url {:?}", config.url))?;
+        let pattern = match &config.pattern {
+            Some(pattern) => version_pattern(pattern)?,
+            None => DEFAULT_PATTERN.clone(),
+        };
+        let url_template = config
+            .url_template
+            .clone()
+            .unwrap_or_else(|| default_url_template(current_download_url, &current_version));
+
+        Ok(Self {
+            url,
+            pattern,
+            url_template,
+            tags: TagMapper::default(),
+            current_version,
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    /// Maps versions back to tags for the `{tag}` placeholder.
+    pub fn with_tags(mut self, tags: TagMapper) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Finds a version in the entry's title, falling back to its links.
+    fn entry_version(&self, entry: &feed_rs::model::Entry) -> Option<LenientVersion> {
+        entry
+            .title
+            .iter()
+            .map(|title| title.content.as_str())
+            .chain(entry.links.iter().map(|link| link.href.as_str()))
+            .filter_map(|text| self.pattern.captures(text)?.name("version"))
+            .find_map(|version| LenientVersion::parse(version.as_str()).ok())
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self) -> Result<()> {
+        let body = CLIENT
+            .get(self.url.clone())
+            .send()
+            .await
+            .wrap_err("failed to load feed")?
+            .error_for_status()?
+            .bytes()
+            .await
+            .wrap_err("failed to get the content of feed")?;
+        let feed = feed_rs::parser::parse(&body[..])
+            .map_err(|e| eyre!("failed to parse feed {}: {}", self.url, e))?;
+        debug!("found {} entries", feed.entries.len());
+
+        let latest_version = feed
+            .entries
+            .iter()
+            .filter_map(|entry| self.entry_version(entry))
+            .filter(|version| version.inner().pre.is_empty())
+            .max();
+
+        if let Some(latest_version) = latest_version {
+            self.remote_url = Some(expand_url_template(
+                &self.url_template,
+                &latest_version,
+                &self.tags,
+            ));
+            self.remote_version = Some(latest_version);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VersionCheck for Feed {
+    fn checker_name(&self) -> &'static str {
+        "feed"
+    }
+
+    async fn fetch_last_version(&mut self, _file_template: &str) -> Result<()> {
+        self.do_fetch_last_version().await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{Feed, VersionCheck};
+    use crate::config::FeedConfig;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+
+    const RELEASES_ATOM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <id>tag:github.com,2008:https://github.com/owner/tool/releases</id>
+  <title>Release notes from tool</title>
+  <updated>2023-05-01T10:00:00Z</updated>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v2.0.0-beta.1</id>
+    <updated>2023-05-01T10:00:00Z</updated>
+    <link rel="alternate" type="text/html" href="https://github.com/owner/tool/releases/tag/v2.0.0-beta.1"/>
+    <title>v2.0.0-beta.1</title>
+  </entry>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v1.5.0</id>
+    <updated>2023-04-01T10:00:00Z</updated>
+    <link rel="alternate" type="text/html" href="https://github.com/owner/tool/releases/tag/v1.5.0"/>
+    <title>Tool 1.5.0</title>
+  </entry>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v1.4.0</id>
+    <updated>2023-03-01T10:00:00Z</updated>
+    <link rel="alternate" type="text/html" href="https://github.com/owner/tool/releases/tag/v1.4.0"/>
+    <title>Spring release</title>
+  </entry>
+</feed>"#;
+
+    #[tokio::test]
+    async fn fetch_last_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/owner/tool/releases.atom"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(RELEASES_ATOM))
+            .mount(&mock_server)
+            .await;
+
+        let config = FeedConfig {
+            url: format!("{}/owner/tool/releases.atom", mock_server.uri()),
+            pattern: None,
+            url_template: Some(
+                "https://github.com/owner/tool/archive/refs/tags/v{version}.tar.gz".to_string(),
+            ),
+        };
+        let mut feed: Box<dyn VersionCheck> = Box::new(
+            Feed::new(
+                "https://github.com/owner/tool/archive/refs/tags/v1.4.0.tar.gz",
+                LenientVersion::parse("1.4.0").unwrap(),
+                &config,
+            )
+            .unwrap(),
+        );
+        feed.fetch_last_version("v1.4.0.tar.gz").await.unwrap();
+
+        assert!(feed.has_newer_version());
+        assert_eq!(
+            feed.get_remote_version(),
+            Some(&LenientVersion::parse("1.5.0").unwrap())
+        );
+        assert_eq!(
+            feed.get_download_url(),
+            Some("https://github.com/owner/tool/archive/refs/tags/v1.5.0.tar.gz")
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_last_version_from_single_digit_versions() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/tool/releases.rss"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Tool releases</title>
+    <link>https://example.org/tool</link>
+    <description>Tool releases</description>
+    <item><title>Tool 10</title></item>
+    <item><title>Tool 9</title></item>
+  </channel>
+</rss>"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let config = FeedConfig {
+            url: format!("{}/tool/releases.rss", mock_server.uri()),
+            pattern: Some("Tool (?P<version>[0-9]+)".to_string()),
+            url_template: None,
+        };
+        let mut feed = Feed::new(
+            "https://example.org/downloads/tool-9.tar.gz",
+            LenientVersion::parse("9").unwrap(),
+            &config,
+        )
+        .unwrap();
+        feed.fetch_last_version("tool-9.tar.gz").await.unwrap();
+
+        assert!(feed.has_newer_version());
+        assert_eq!(
+            feed.get_download_url(),
+            Some("https://example.org/downloads/tool-10.tar.gz")
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result, Section, SectionExt,
+};
+use tokio::process::Command;
+use tracing::{debug, instrument};
+
+use super::tag_mapping::TagMapper;
+use super::{default_url_template, expand_url_template, VersionCheck};
+use crate::config::GitConfig;
+use crate::version::LenientVersion;
+
+/// Checker for upstreams that only publish a git repository, tags are listed with
+/// `git ls-remote` so it works with any host git can talk to.
+#[derive(Debug)]
+pub struct Git {
+    remote: String,
+    url_template: String,
+    tags: TagMapper,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl Git {
+    pub fn new(
+        current_download_url: &str,
+        current_version: LenientVersion,
+        config: &GitConfig,
+    ) -> Self {
+        let url_template = config
+            .url_template
+            .clone()
+            .unwrap_or_else(|| default_url_template(current_download_url, &current_version));
+        Self {
+            remote: config.remote.clone(),
+            url_template,
+            tags: TagMapper::default(),
+            current_version,
+            remote_version: None,
+            remote_url: None,
+        }
+    }
+
+    /// Reads versions out of tags, and back for the `{tag}` placeholder.
+    pub fn with_tags(mut self, tags: TagMapper) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self) -> Result<()> {
+        let output = Command::new("git")
+            .args(["ls-remote", "--tags", "--refs"])
+            .arg(&self.remote)
+            .output()
+            .await
+            .wrap_err("failed to run git ls-remote")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(eyre!("failed to list tags of {:?}", self.remote)
+                .section(stderr.to_string().header("Stderr")));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let latest_version = parse_tags(&stdout)
+            .filter_map(|tag| self.tags.version(tag))
+            .filter(|version| version.inner().pre.is_empty())
+            .max();
+
+        if let Some(latest_version) = latest_version {
+            debug!("found latest tag {}", latest_version.original_value());
+            self.remote_url = Some(expand_url_template(
+                &self.url_template,
+                &latest_version,
+                &self.tags,
+            ));
+            self.remote_version = Some(latest_version);
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts tag names out of `git ls-remote --tags` output.
+fn parse_tags(output: &str) -> impl Iterator<Item = &str> {
+    output
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .filter_map(|reference| reference.strip_prefix("refs/tags/"))
+        .filter(|tag| !tag.ends_with("^{}"))
+}
+
+#[async_trait]
+impl VersionCheck for Git {
+    fn checker_name(&self) -> &'static str {
+        "git"
+    }
+
+    async fn fetch_last_version(&mut self, _file_template: &str) -> Result<()> {
+        self.do_fetch_last_version().await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use tokio::process::Command;
+
+    use super::{Git, VersionCheck};
+    use crate::config::GitConfig;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+
+    async fn git(args: &[&str], directory: &TempDir) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(directory.as_ref())
+            .status()
+            .await
+            .unwrap();
+        assert!(status.success());
+    }
+
+    async fn setup_tagged_repository(tags: &[&str]) -> TempDir {
+        let remote_repository = TempDir::new("aur-autoupdater-git").unwrap();
+        git(&["init", "--bare"], &remote_repository).await;
+
+        let work_repository = TempDir::new("aur-autoupdater-git-work").unwrap();
+        git(&["init"], &work_repository).await;
+        git(
+            &["commit", "--allow-empty", "-m", "Initial"],
+            &work_repository,
+        )
+        .await;
+        for tag in tags {
+            git(&["tag", "-a", tag, "-m", tag], &work_repository).await;
+        }
+        let remote = remote_repository.as_ref().to_string_lossy().to_string();
+        git(&["push", &remote, "--tags"], &work_repository).await;
+
+        remote_repository
+    }
+
+    #[tokio::test]
+    async fn fetch_last_version() {
+        setup_error_handlers().ok();
+        let repository =
+            setup_tagged_repository(&["v1.0.0", "v1.1.0", "v1.2.0-rc1", "nightly"]).await;
+        let config = GitConfig {
+            remote: repository.as_ref().to_string_lossy().to_string(),
+            url_template: None,
+        };
+
+        let mut git: Box<dyn VersionCheck> = Box::new(Git::new(
+            "https://git.example.org/tool/snapshot/tool-1.0.0.tar.gz",
+            LenientVersion::parse("1.0.0").unwrap(),
+            &config,
+        ));
+        git.fetch_last_version("tool-1.0.0.tar.gz").await.unwrap();
+
+        assert!(git.has_newer_version());
+        assert_eq!(git.get_remote_version().unwrap().original_value(), "v1.1.0");
+        assert_eq!(
+            git.get_download_url(),
+            Some("https://git.example.org/tool/snapshot/tool-1.1.0.tar.gz")
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use async_trait::async_trait;
+use color_eyre::{eyre::eyre, Result};
+
+use serde::Deserialize;
+use tracing::{debug, instrument};
+use url::Url;
+
+use super::tag_mapping::TagMapper;
+use super::{get_paginated, url_file_name, VersionCheck};
+use crate::package::VERSION_PLACEHOLDER;
+use crate::version::LenientVersion;
+
+/// Largest page size Gitea allows by default, it returns 30 items otherwise.
+const PER_PAGE: usize = 50;
+
+/// Checker for Gitea and its forks like Forgejo, which powers Codeberg.
+#[derive(Debug)]
+pub struct Gitea {
+    gitea_base_url: String,
+    owner: String,
+    repository: String,
+    tags: TagMapper,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl Gitea {
+    pub fn new(url: &Url, current_version: LenientVersion) -> Result<Self> {
+        let gitea_base_url = format!("{}/api/v1", url.origin().ascii_serialization());
+        Self::with_gitea_url(url, current_version, gitea_base_url)
+    }
+
+    pub(crate) fn with_gitea_url(
+        url: &Url,
+        current_version: LenientVersion,
+        gitea_base_url: String,
+    ) -> Result<Self> {
+        let mut path = url.path().split('/');
+        let owner = path
+            .nth(1)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| eyre!("failed to get owner from {:?}", &url))?
+            .to_string();
+
+        let repository = path
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| eyre!("failed to get repository from {:?}", &url))?
+            .to_string();
+
+        Ok(Self {
+            gitea_base_url,
+            owner,
+            repository,
+            tags: TagMapper::default(),
+            current_version,
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    /// Reads versions out of tags with `tags` instead of parsing them as they are.
+    pub fn with_tags(mut self, tags: TagMapper) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let releases_url = format!(
+            "{}/repos/{}/{}/releases?limit={}",
+            self.gitea_base_url, self.owner, self.repository, PER_PAGE
+        );
+        let releases: Vec<Release> = get_paginated(releases_url).await?;
+        let mut latest_version: Option<LenientVersion> = None;
+        let mut download_url: Option<String> = None;
+
+        debug!("found {} releases", releases.len());
+
+        for release in releases.iter().filter(|r| !r.draft) {
+            if let Some(tag_name) = self.tags.version(&release.tag_name).as_ref() {
+                debug!("checking tag {}", tag_name);
+                let file_name = file_template.replace(VERSION_PLACEHOLDER, &tag_name.to_string());
+                let asset_urls = release
+                    .assets
+                    .iter()
+                    .map(|asset| asset.browser_download_url.as_str())
+                    .chain(release.tarball_url.as_deref());
+
+                for asset_url in asset_urls {
+                    if url_file_name(asset_url) != file_name {
+                        continue;
+                    }
+                    if latest_version.as_ref().is_none_or(|l| tag_name > l) {
+                        latest_version = Some(tag_name.clone());
+                        download_url = Some(asset_url.to_string());
+                    }
+                }
+            }
+        }
+
+        if let Some(latest_version) = latest_version {
+            self.remote_version = Some(latest_version);
+            self.remote_url = download_url;
+            return Ok(());
+        }
+
+        debug!("Falling back to tags as no releases lead to a newer version");
+        let tags_url = format!(
+            "{}/repos/{}/{}/tags?limit={}",
+            self.gitea_base_url, self.owner, self.repository, PER_PAGE
+        );
+        let tags: Vec<Tag> = get_paginated(tags_url).await?;
+        debug!("found {} tags", tags.len());
+
+        for tag in &tags {
+            if let Some(tag_name) = self.tags.version(&tag.name).as_ref() {
+                debug!("checking tag {}", tag_name);
+                if latest_version.as_ref().is_none_or(|l| tag_name > l) {
+                    latest_version = Some(tag_name.clone());
+                    download_url = Some(tag.tarball_url.clone());
+                }
+            }
+        }
+
+        if let Some(latest_version) = latest_version {
+            self.remote_version = Some(latest_version);
+            self.remote_url = download_url;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<Attachment>,
+    tarball_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Attachment {
+    browser_download_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+    tarball_url: String,
+}
+
+#[async_trait]
+impl VersionCheck for Gitea {
+    fn checker_name(&self) -> &'static str {
+        "gitea"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{Gitea, VersionCheck};
+    use crate::version::LenientVersion;
+    use crate::{package::VERSION_PLACEHOLDER, setup_error_handlers};
+
+    #[tokio::test]
+    async fn fetch_last_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .and(query_param("limit", "50"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        format!(
+                            r#"<{}/repos/owner/tool/releases?page=2>; rel="next""#,
+                            mock_server.uri()
+                        )
+                        .as_str(),
+                    )
+                    .set_body_json(json!([{
+                        "tag_name": "v1.3.0",
+                        "draft": true,
+                        "assets": [],
+                        "tarball_url": "https://codeberg.org/owner/tool/archive/v1.3.0.tar.gz"
+                    }])),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "tag_name": "v1.2.0",
+                "draft": false,
+                "assets": [{
+                    "name": "tool-1.2.0-linux-amd64.tar.gz.sha256",
+                    "browser_download_url": "https://codeberg.org/owner/tool/releases/download/v1.2.0/tool-1.2.0-linux-amd64.tar.gz.sha256"
+                }, {
+                    "name": "tool-1.2.0-linux-amd64.tar.gz",
+                    "browser_download_url": "https://codeberg.org/owner/tool/releases/download/v1.2.0/tool-1.2.0-linux-amd64.tar.gz"
+                }],
+                "tarball_url": "https://codeberg.org/owner/tool/archive/v1.2.0.tar.gz"
+            }])))
+            .mount(&mock_server)
+            .await;
+
+        let mut gitea: Box<dyn VersionCheck> = Box::new(
+            Gitea::with_gitea_url(
+                &"https://codeberg.org/owner/tool/releases/download/v1.1.0/tool-1.1.0-linux-amd64.tar.gz"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("1.1.0").unwrap(),
+                mock_server.uri(),
+            )
+            .unwrap(),
+        );
+
+        gitea
+            .fetch_last_version(&format!("tool-{}-linux-amd64.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+
+        assert!(gitea.has_newer_version());
+        assert_eq!(
+            gitea.get_remote_version(),
+            Some(&LenientVersion::parse("1.2.0").unwrap())
+        );
+        assert_eq!(
+            gitea.get_download_url(),
+            Some("https://codeberg.org/owner/tool/releases/download/v1.2.0/tool-1.2.0-linux-amd64.tar.gz")
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_last_version_from_tags() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/tags"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"name": "v2.0.0", "tarball_url": "https://codeberg.org/owner/tool/archive/v2.0.0.tar.gz"},
+                {"name": "v1.1.0", "tarball_url": "https://codeberg.org/owner/tool/archive/v1.1.0.tar.gz"}
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let mut gitea = Gitea::with_gitea_url(
+            &"https://codeberg.org/owner/tool/archive/v1.1.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.1.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+
+        gitea
+            .fetch_last_version(&format!("v{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+
+        assert!(gitea.has_newer_version());
+        assert_eq!(
+            gitea.get_download_url(),
+            Some("https://codeberg.org/owner/tool/archive/v2.0.0.tar.gz")
+        );
+    }
+}
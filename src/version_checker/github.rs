@@ -1,17 +1,91 @@
-use async_trait::async_trait;
-use color_eyre::{eyre::eyre, Result};
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::header::{HeaderMap, ACCEPT, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 use url::Url;
 
-use super::VersionCheck;
+use super::tag_mapping::TagMapper;
+use super::{next_link, version_pattern, VersionCheck};
+use crate::config::{AssetPattern, Config, PackageConfig, PrereleasePolicy};
 use crate::version::LenientVersion;
 use crate::{package::VERSION_PLACEHOLDER, CLIENT};
 
+/// Longest wait for a rate limit to reset before giving up.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+const MAX_ATTEMPTS: usize = 3;
+const PER_PAGE: usize = 100;
+pub const DEFAULT_MAX_PAGES: usize = 10;
+
+lazy_static! {
+    /// Last rate limit reported by GitHub, shared by every checker of a run.
+    static ref RATE_LIMIT: Mutex<Option<RateLimit>> = Mutex::new(None);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    /// Unix timestamp of the next reset.
+    pub reset: u64,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.parse().ok();
+        Some(Self {
+            limit: header("x-ratelimit-limit")?,
+            remaining: header("x-ratelimit-remaining")?,
+            reset: header("x-ratelimit-reset")?,
+        })
+    }
+
+    fn until_reset(&self) -> Duration {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Duration::from_secs(self.reset.saturating_sub(now))
+    }
+}
+
+/// Rate limit reported by the last GitHub API response, if any request was made.
+pub fn last_rate_limit() -> Option<RateLimit> {
+    *RATE_LIMIT.lock().unwrap()
+}
+
+/// Keeps the token out of logs.
+struct Token(String);
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Token(..)")
+    }
+}
+
 #[derive(Debug)]
 pub struct Github {
     github_base_url: String,
+    token: Option<Token>,
+    max_pages: usize,
+    prerelease_policy: PrereleasePolicy,
+    /// Matched against whole asset names instead of the file template.
+    asset_pattern: Option<Regex>,
+    tags: TagMapper,
+    /// Scheme and host of the web interface, archives are downloaded from there.
+    web_base_url: String,
     organization: String,
     repository: String,
     current_version: LenientVersion,
@@ -20,12 +94,21 @@ pub struct Github {
 }
 
 impl Github {
-    pub fn new(url: &Url, current_version: LenientVersion) -> Result<Self> {
-        Self::with_default_github_url(url, current_version)
-    }
-
-    fn with_default_github_url(url: &Url, current_version: LenientVersion) -> Result<Self> {
-        Self::with_github_url(url, current_version, "https://api.github.com".to_string())
+    pub fn new(
+        url: &Url,
+        current_version: LenientVersion,
+        config: &Config,
+        package: &PackageConfig,
+    ) -> Result<Self> {
+        let host = url.host_str().unwrap_or_default();
+        let github_base_url = config.github_api_url(host);
+        let mut github = Self::with_github_url(url, current_version, github_base_url)?;
+        github.token = config.github_token(host).map(Token);
+        github.max_pages = config.github_max_pages;
+        github.prerelease_policy = package.prerelease;
+        github.asset_pattern = package.asset.as_ref().map(asset_regex).transpose()?;
+        github.tags = TagMapper::new(package.tag.as_ref())?;
+        Ok(github)
     }
 
     fn with_github_url(
@@ -46,6 +129,12 @@ impl Github {
 
         Ok(Self {
             github_base_url,
+            token: None,
+            max_pages: DEFAULT_MAX_PAGES,
+            prerelease_policy: PrereleasePolicy::Never,
+            asset_pattern: None,
+            tags: TagMapper::default(),
+            web_base_url: url.origin().ascii_serialization(),
             organization,
             repository,
             current_version,
@@ -54,83 +143,278 @@ impl Github {
         })
     }
 
+    /// Reads every page of a listing until `is_old_page` says the rest can only hold older
+    /// versions, or the page cap is reached.
+    async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        is_old_page: impl Fn(&[T]) -> bool,
+    ) -> Result<Vec<T>> {
+        let mut items = vec![];
+        let mut next = Some(format!("{}?per_page={}", url, PER_PAGE));
+        let mut pages = 0;
+        while let Some(url) = next.take() {
+            if pages == self.max_pages {
+                debug!("stopping after {} pages", pages);
+                break;
+            }
+            pages += 1;
+            let (page, next_url): (Vec<T>, _) = self.get_page(&url).await?;
+            let is_old = is_old_page(&page);
+            items.extend(page);
+            if is_old {
+                debug!("stopping as page {} only has older versions", pages);
+                break;
+            }
+            next = next_url;
+        }
+        Ok(items)
+    }
+
+    /// Whether a page only has versions up to the current one, release listings are newest
+    /// first.
+    fn only_older_versions<'a>(&self, names: impl Iterator<Item = &'a str>) -> bool {
+        let mut versions = names.filter_map(|name| self.tags.version(name)).peekable();
+        versions.peek().is_some() && versions.all(|version| version <= self.current_version)
+    }
+
+    /// Requests the API, waiting for short rate limit resets and failing clearly on long ones.
+    /// Returns the url of the next page along with the body.
+    async fn get_page<T: DeserializeOwned>(&self, url: &str) -> Result<(T, Option<String>)> {
+        for attempt in 1..=MAX_ATTEMPTS {
+            let mut request = CLIENT
+                .get(url)
+                .header(ACCEPT, "application/vnd.github+json");
+            if let Some(Token(token)) = &self.token {
+                request = request.bearer_auth(token);
+            }
+            let response = request
+                .send()
+                .await
+                .wrap_err_with(|| format!("failed to request {}", url))?;
+            let rate_limit = RateLimit::from_headers(response.headers());
+            if rate_limit.is_some() {
+                *RATE_LIMIT.lock().unwrap() = rate_limit;
+            }
+
+            let status = response.status();
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|r| r.to_str().ok()?.parse().ok())
+                .map(Duration::from_secs);
+            let exhausted = rate_limit.is_some_and(|r| r.remaining == 0);
+            let limited = status == StatusCode::TOO_MANY_REQUESTS
+                || (status == StatusCode::FORBIDDEN && (exhausted || retry_after.is_some()));
+            if !limited {
+                let next = next_link(response.headers());
+                let body = response
+                    .error_for_status()?
+                    .json()
+                    .await
+                    .wrap_err_with(|| format!("failed to decode response of {}", url))?;
+                return Ok((body, next));
+            }
+
+            let wait = retry_after
+                .or_else(|| rate_limit.map(|r| r.until_reset()))
+                .unwrap_or(MAX_RATE_LIMIT_WAIT);
+            if wait > MAX_RATE_LIMIT_WAIT {
+                return Err(eyre!(
+                    "GitHub rate limit exceeded, it resets in {} seconds{}",
+                    wait.as_secs(),
+                    if self.token.is_none() {
+                        ", configure a token to get a higher limit"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            if attempt == MAX_ATTEMPTS {
+                break;
+            }
+            warn!(
+                "GitHub rate limit hit, retrying in {} seconds",
+                wait.as_secs()
+            );
+            tokio::time::sleep(wait).await;
+        }
+        Err(eyre!(
+            "GitHub rate limit exceeded requesting {} after {} attempts",
+            url,
+            MAX_ATTEMPTS
+        ))
+    }
+
+    /// Assets of `release` whose whole name matches the asset pattern, or the file name of
+    /// `file_template` when there is none.
+    fn matching_assets<'a>(
+        &self,
+        release: &'a Release,
+        version: &LenientVersion,
+        file_template: &str,
+    ) -> Vec<&'a Asset> {
+        let file_name = file_template
+            .rsplit('/')
+            .next()
+            .unwrap_or(file_template)
+            .replace(VERSION_PLACEHOLDER, &version.to_string());
+        release
+            .assets
+            .iter()
+            .filter(|asset| match &self.asset_pattern {
+                Some(pattern) => pattern
+                    .captures(&asset.name)
+                    .and_then(|captures| captures.name("version"))
+                    .is_some_and(|captured| {
+                        LenientVersion::parse(captured.as_str()).is_ok_and(|v| &v == version)
+                    }),
+                None => asset.name == file_name,
+            })
+            .collect()
+    }
+
     #[instrument]
     async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
         let releases_url = format!(
             "{}/repos/{}/{}/releases",
             self.github_base_url, self.organization, self.repository
         );
-        let response = CLIENT.get(&releases_url).send().await?;
-        let releases: Vec<Release> = response.json().await?;
-        let mut latest_version: Option<LenientVersion> = None;
-        let mut download_url: Option<String> = None;
-
+        let releases: Vec<Release> = self
+            .get_paginated(&releases_url, |releases: &[Release]| {
+                self.only_older_versions(releases.iter().map(|r| r.tag_name.as_str()))
+            })
+            .await?;
         debug!("found {} release", releases.len());
 
-        for release in &releases {
-            if let Ok(tag_name) = LenientVersion::parse(&release.tag_name).as_ref() {
+        let mut candidates = vec![];
+        for release in releases.iter().filter(|release| !release.draft) {
+            if let Some(tag_name) = self.tags.version(&release.tag_name) {
                 debug!("checking tag {}", tag_name);
-                for asset in &release.assets {
-                    let file_name =
-                        file_template.replace(VERSION_PLACEHOLDER, &tag_name.to_string());
-
-                    if asset.browser_download_url.contains(&file_name) {
-                        if let Some(current_latest_version) = latest_version.as_ref() {
-                            if tag_name > current_latest_version {
-                                latest_version = Some(tag_name.clone());
-                                download_url = Some(asset.browser_download_url.clone());
-                            }
-                        } else {
-                            latest_version = Some(tag_name.clone());
-                            download_url = Some(asset.browser_download_url.clone());
-                        }
+                let asset = match self.matching_assets(release, &tag_name, file_template)[..] {
+                    [] => continue,
+                    [asset] => asset,
+                    ref assets if tag_name > self.current_version => {
+                        return Err(eyre!(
+                            "several assets of release {} match, candidates: {}",
+                            release.tag_name,
+                            assets
+                                .iter()
+                                .map(|asset| asset.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
                     }
-                }
+                    _ => continue,
+                };
+                candidates.push(Candidate {
+                    prerelease: release.prerelease || !tag_name.inner().pre.is_empty(),
+                    version: tag_name,
+                    download_url: asset.browser_download_url.clone(),
+                });
             }
         }
 
-        if let Some(latest_version) = latest_version {
-            self.remote_version = Some(latest_version);
-            self.remote_url = download_url;
+        if let Some(latest) = pick_latest(self.prerelease_policy, candidates) {
+            self.remote_version = Some(latest.version);
+            self.remote_url = Some(latest.download_url);
             return Ok(());
         }
 
+        // Tags of flagged releases are no more stable than the releases themselves
+        let drafts: HashSet<&str> = releases
+            .iter()
+            .filter(|release| release.draft)
+            .map(|release| release.tag_name.as_str())
+            .collect();
+        let prereleases: HashSet<&str> = releases
+            .iter()
+            .filter(|release| release.prerelease)
+            .map(|release| release.tag_name.as_str())
+            .collect();
+
         debug!("Falling back to tags as no releases lead to a newer version");
         let tags_url = format!(
             "{}/repos/{}/{}/tags",
             self.github_base_url, self.organization, self.repository
         );
-        let response = CLIENT.get(&tags_url).send().await?;
-        let tags: Vec<Tag> = response.json().await?;
+        // Tags are not listed by version, so only the page cap ends the listing
+        let tags: Vec<Tag> = self.get_paginated(&tags_url, |_: &[Tag]| false).await?;
         debug!("found {} tags", tags.len());
 
-        for tag in &tags {
-            if let Ok(tag_name) = LenientVersion::parse(&tag.name).as_ref() {
-                debug!("checking tag {}", tag_name);
-                if let Some(current_latest_version) = latest_version.as_ref() {
-                    if tag_name > current_latest_version {
-                        latest_version = Some(tag_name.clone());
-                        download_url = Some(tag.get_download_url(self));
-                    }
-                } else {
-                    latest_version = Some(tag_name.clone());
-                    download_url = Some(tag.get_download_url(self));
-                }
-            }
-        }
+        let candidates = tags
+            .iter()
+            .filter(|tag| !drafts.contains(tag.name.as_str()))
+            .filter_map(|tag| {
+                let version = self.tags.version(&tag.name)?;
+                debug!("checking tag {}", version);
+                Some(Candidate {
+                    prerelease: prereleases.contains(tag.name.as_str())
+                        || !version.inner().pre.is_empty(),
+                    download_url: Tag::get_download_url(self, &version),
+                    version,
+                })
+            })
+            .collect();
 
-        if let Some(latest_version) = latest_version {
-            self.remote_version = Some(latest_version);
-            self.remote_url = download_url;
+        if let Some(latest) = pick_latest(self.prerelease_policy, candidates) {
+            self.remote_version = Some(latest.version);
+            self.remote_url = Some(latest.download_url);
         }
 
         Ok(())
     }
 }
 
+/// Compiles an asset pattern into a regex matching whole asset names.
+fn asset_regex(pattern: &AssetPattern) -> Result<Regex> {
+    let pattern = match pattern {
+        AssetPattern::Regex(regex) => regex.clone(),
+        AssetPattern::Glob(glob) => regex::escape(glob)
+            .replace(r"\*", ".*")
+            .replace(r"\{version\}", r"(?P<version>[0-9][0-9A-Za-z.+~_-]*?)"),
+    };
+    version_pattern(&format!("^(?:{})$", pattern))
+}
+
+struct Candidate {
+    version: LenientVersion,
+    download_url: String,
+    /// Flagged as such on GitHub or with a prerelease version.
+    prerelease: bool,
+}
+
+/// Picks the newest candidate allowed by `policy`, candidates come newest first so the first one
+/// wins on equal versions.
+fn pick_latest(policy: PrereleasePolicy, candidates: Vec<Candidate>) -> Option<Candidate> {
+    let newest = |candidates: Vec<Candidate>| {
+        candidates
+            .into_iter()
+            .rev()
+            .max_by(|a, b| a.version.cmp(&b.version))
+    };
+    let (prereleases, stable): (Vec<_>, Vec<_>) =
+        candidates.into_iter().partition(|c| c.prerelease);
+    match policy {
+        PrereleasePolicy::Never => newest(stable),
+        PrereleasePolicy::Allow => newest(stable.into_iter().chain(prereleases).collect()),
+        PrereleasePolicy::IfNewerThanStable => match (newest(stable), newest(prereleases)) {
+            (Some(stable), Some(prerelease)) if prerelease.version > stable.version => {
+                Some(prerelease)
+            }
+            (Some(stable), _) => Some(stable),
+            (None, prerelease) => prerelease,
+        },
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Release {
     tag_name: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
     assets: Vec<Asset>,
 }
 
@@ -140,10 +424,14 @@ struct Tag {
 }
 
 impl Tag {
-    fn get_download_url(&self, github: &Github) -> String {
+    /// Archive of the tag `version` was read from.
+    fn get_download_url(github: &Github, version: &LenientVersion) -> String {
         format!(
-            "https://github.com/{}/{}/archive/refs/tags/{}.tar.gz",
-            github.organization, github.repository, self.name
+            "{}/{}/{}/archive/refs/tags/{}.tar.gz",
+            github.web_base_url,
+            github.organization,
+            github.repository,
+            github.tags.tag(version)
         )
     }
 }
@@ -180,14 +468,16 @@ impl VersionCheck for Github {
 #[cfg(test)]
 mod tests {
     use std::env::set_var;
+    use std::time::{Duration, Instant};
 
     use serde_json::json;
     use wiremock::{
-        matchers::{method, path},
+        matchers::{header, method, path, query_param, query_param_is_missing},
         Mock, MockServer, ResponseTemplate,
     };
 
-    use super::{Github, VersionCheck};
+    use super::{asset_regex, last_rate_limit, Github, TagMapper, Token, VersionCheck};
+    use crate::config::{AssetPattern, Config, PackageConfig, PrereleasePolicy, TagMapping};
     use crate::version::LenientVersion;
     use crate::{package::VERSION_PLACEHOLDER, setup_error_handlers};
 
@@ -226,4 +516,511 @@ mod tests {
         assert_eq!(github.get_current_version(), &current_version);
         assert_eq!(github.get_remote_version().unwrap(), &remote_version);
     }
+
+    #[tokio::test]
+    async fn fetch_last_version_from_enterprise_tags() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/repos/owner/tool/releases"))
+            .and(header("Authorization", "Bearer corporate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/repos/owner/tool/tags"))
+            .and(header("Authorization", "Bearer corporate"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([{"name": "v1.1.0"}, {"name": "v1.0.0"}])),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let config = Config::parse(&format!(
+            r#"
+            github-token = "public"
+
+            [github-hosts."git.corp.example"]
+            api-url = "{}/api/v3/"
+            token = "corporate"
+            "#,
+            mock_server.uri()
+        ))
+        .unwrap();
+        let mut github = Github::new(
+            &"https://git.corp.example/owner/tool/archive/refs/tags/v1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("v1.0.0").unwrap(),
+            &config,
+            &PackageConfig::default(),
+        )
+        .unwrap();
+        github
+            .fetch_last_version(&format!("v{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+
+        assert!(github.has_newer_version());
+        assert_eq!(
+            github.get_download_url(),
+            Some("https://git.corp.example/owner/tool/archive/refs/tags/v1.1.0.tar.gz")
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_after_rate_limit() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .and(header("Authorization", "Bearer secret"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-RateLimit-Limit", "5000")
+                    .insert_header("X-RateLimit-Remaining", "4321")
+                    .insert_header("X-RateLimit-Reset", "1700000000")
+                    .set_body_json(json!([{
+                        "tag_name": "v1.1.0",
+                        "assets": [{
+                            "name": "tool-1.1.0.tar.gz",
+                            "browser_download_url": "https://github.com/owner/tool/releases/download/v1.1.0/tool-1.1.0.tar.gz"
+                        }]
+                    }])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/releases/download/v1.0.0/tool-1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        github.token = Some(Token("secret".to_string()));
+        github
+            .fetch_last_version(&format!("tool-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(github.has_newer_version());
+        assert_eq!(last_rate_limit().unwrap().remaining, 4321);
+        assert!(!format!("{:?}", github).contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn fails_clearly_when_rate_limited() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(403).insert_header("Retry-After", "3600"))
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/releases/download/v1.0.0/tool-1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        let error = github
+            .fetch_last_version(&format!("tool-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("rate limit"));
+    }
+
+    #[tokio::test]
+    async fn gives_up_without_waiting_after_last_attempt() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/releases/download/v1.0.0/tool-1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        let started = Instant::now();
+        let error = github
+            .fetch_last_version(&format!("tool-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap_err();
+        mock_server.verify().await;
+
+        assert!(error.to_string().contains("after 3 attempts"));
+        assert!(started.elapsed() < Duration::from_millis(2900));
+    }
+
+    #[tokio::test]
+    async fn follows_release_pages() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        let release = |tag: &str, file: &str| {
+            json!({
+                "tag_name": tag,
+                "assets": [{
+                    "name": file,
+                    "browser_download_url": format!("https://github.com/owner/tool/releases/download/{}/{}", tag, file)
+                }]
+            })
+        };
+        let next = |page: usize| {
+            format!(
+                r#"<{}/repos/owner/tool/releases?per_page=100&page={}>; rel="next""#,
+                mock_server.uri(),
+                page
+            )
+        };
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .and(query_param_is_missing("page"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Link", next(2).as_str())
+                    .set_body_json(json!([release("v3.0.0", "other-3.0.0.tar.gz")])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .and(query_param("page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Link", next(3).as_str())
+                    .set_body_json(json!([release("v1.2.0", "tool-1.2.0.tar.gz")])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .and(query_param("page", "3"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Link", next(4).as_str())
+                    .set_body_json(json!([release("v0.9.0", "tool-0.9.0.tar.gz")])),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .and(query_param("page", "4"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/releases/download/v1.0.0/tool-1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        github
+            .fetch_last_version(&format!("tool-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert_eq!(
+            github.get_download_url(),
+            Some("https://github.com/owner/tool/releases/download/v1.2.0/tool-1.2.0.tar.gz")
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_every_tag_page() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&mock_server)
+            .await;
+        let next = format!(
+            r#"<{}/repos/owner/tool/tags?per_page=100&page=2>; rel="next""#,
+            mock_server.uri()
+        );
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/tags"))
+            .and(query_param_is_missing("page"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Link", next.as_str())
+                    .set_body_json(json!([{"name": "v1.9.5"}, {"name": "v1.2.0"}])),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/tags"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"name": "v1.10.0"}])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/archive/refs/tags/v1.9.5.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.9.5").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        github
+            .fetch_last_version(&format!("v{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert_eq!(
+            github.get_remote_version().unwrap().original_value(),
+            "v1.10.0"
+        );
+    }
+
+    #[tokio::test]
+    async fn respects_prerelease_policy() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        let release = |tag: &str, prerelease: bool, draft: bool| {
+            json!({
+                "tag_name": tag,
+                "prerelease": prerelease,
+                "draft": draft,
+                "assets": [{
+                    "name": format!("tool-{}.tar.gz", &tag[1..]),
+                    "browser_download_url": format!("https://github.com/owner/tool/releases/download/{0}/tool-{1}.tar.gz", tag, &tag[1..])
+                }]
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                release("v3.0.0", false, true),
+                release("v2.1.0-rc.1", false, false),
+                release("v2.0.0", true, false),
+                release("v1.1.0", false, false),
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/releases/download/v1.0.0/tool-1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        let file_template = format!("tool-{}.tar.gz", VERSION_PLACEHOLDER);
+        github.fetch_last_version(&file_template).await.unwrap();
+        assert_eq!(
+            github.get_remote_version().unwrap().original_value(),
+            "v1.1.0"
+        );
+
+        github.prerelease_policy = PrereleasePolicy::IfNewerThanStable;
+        github.fetch_last_version(&file_template).await.unwrap();
+        assert_eq!(
+            github.get_remote_version().unwrap().original_value(),
+            "v2.1.0-rc.1"
+        );
+    }
+
+    #[tokio::test]
+    async fn matches_whole_asset_names() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        let asset = |name: &str| {
+            json!({
+                "name": name,
+                "browser_download_url": format!("https://github.com/owner/tool/releases/download/v1.1.0/{}", name)
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "tag_name": "v1.1.0",
+                "assets": [
+                    asset("tool-1.1.0-linux_x86_64.tar.gz.sha256"),
+                    asset("tool-1.1.0-linux_x86_64.tar.gz"),
+                    asset("tool-linux_x86_64.tar.gz"),
+                ]
+            }])))
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/releases/download/v1.0.0/tool-1.0.0-linux-amd64.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        let file_template = format!("tool-{}-linux-amd64.tar.gz", VERSION_PLACEHOLDER);
+
+        github.asset_pattern = Some(
+            asset_regex(&AssetPattern::Glob(
+                "tool-{version}-linux_x86_64.tar.gz".to_string(),
+            ))
+            .unwrap(),
+        );
+        github.fetch_last_version(&file_template).await.unwrap();
+        assert_eq!(
+            github.get_download_url(),
+            Some("https://github.com/owner/tool/releases/download/v1.1.0/tool-1.1.0-linux_x86_64.tar.gz")
+        );
+
+        github.asset_pattern = Some(
+            asset_regex(&AssetPattern::Regex(
+                r"tool-(?P<version>[0-9.]+)-linux_(amd64|x86_64)\.tar\.gz.*".to_string(),
+            ))
+            .unwrap(),
+        );
+        let error = github
+            .fetch_last_version(&file_template)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("tool-1.1.0-linux_x86_64.tar.gz.sha256, tool-1.1.0-linux_x86_64.tar.gz")
+        );
+
+        // Assets the optional version group does not take part in are skipped
+        github.asset_pattern = Some(
+            asset_regex(&AssetPattern::Regex(
+                r"tool(-(?P<version>[0-9.]+))?-linux_x86_64\.tar\.gz".to_string(),
+            ))
+            .unwrap(),
+        );
+        github.fetch_last_version(&file_template).await.unwrap();
+        assert_eq!(
+            github.get_download_url(),
+            Some("https://github.com/owner/tool/releases/download/v1.1.0/tool-1.1.0-linux_x86_64.tar.gz")
+        );
+
+        assert!(asset_regex(&AssetPattern::Regex("tool-.*".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn skips_tags_of_flagged_releases() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"tag_name": "v3.0.0", "draft": true, "assets": []},
+                {
+                    "tag_name": "v2.0.0",
+                    "prerelease": true,
+                    "assets": [{
+                        "name": "tool-2.0.0.tar.gz",
+                        "browser_download_url": "https://github.com/owner/tool/releases/download/v2.0.0/tool-2.0.0.tar.gz"
+                    }]
+                }
+            ])))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/tool/tags"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"name": "v3.0.0"},
+                {"name": "v2.0.0"},
+                {"name": "v1.1.0"},
+                {"name": "v1.0.0"}
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/tool/archive/refs/tags/v1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        let file_template = format!("v{}.tar.gz", VERSION_PLACEHOLDER);
+        github.fetch_last_version(&file_template).await.unwrap();
+        assert_eq!(
+            github.get_download_url(),
+            Some("https://github.com/owner/tool/archive/refs/tags/v1.1.0.tar.gz")
+        );
+
+        github.prerelease_policy = PrereleasePolicy::Allow;
+        github.fetch_last_version(&file_template).await.unwrap();
+        assert_eq!(
+            github.get_remote_version().unwrap().original_value(),
+            "v2.0.0"
+        );
+    }
+
+    #[tokio::test]
+    async fn maps_monorepo_tags() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/mono/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/mono/tags"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"name": "server/v2.0.0"},
+                {"name": "cli/v1.1.0"},
+                {"name": "cli/v1.0.0"}
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let mut github = Github::with_github_url(
+            &"https://github.com/owner/mono/archive/refs/tags/cli/v1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+        github.tags = TagMapper::new(Some(&TagMapping::Prefix("cli/".to_string()))).unwrap();
+        github
+            .fetch_last_version(&format!("v{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+
+        assert_eq!(github.get_remote_version().unwrap().to_string(), "1.1.0");
+        assert_eq!(
+            github.get_download_url(),
+            Some("https://github.com/owner/mono/archive/refs/tags/cli/v1.1.0.tar.gz")
+        );
+    }
 }
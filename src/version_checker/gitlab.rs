@@ -0,0 +1,340 @@
+use async_trait::async_trait;
+use color_eyre::{eyre::eyre, Result};
+
+use serde::Deserialize;
+use tracing::{debug, instrument};
+use url::{form_urlencoded, Url};
+
+use super::tag_mapping::TagMapper;
+use super::{get_paginated, url_file_name, VersionCheck};
+use crate::package::VERSION_PLACEHOLDER;
+use crate::version::LenientVersion;
+
+/// Largest page size GitLab allows, it defaults to 20.
+const PER_PAGE: usize = 100;
+
+#[derive(Debug)]
+pub struct GitLab {
+    gitlab_base_url: String,
+    web_base_url: String,
+    project_path: String,
+    tags: TagMapper,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl GitLab {
+    pub fn new(url: &Url, current_version: LenientVersion) -> Result<Self> {
+        let gitlab_base_url = format!("{}/api/v4", url.origin().ascii_serialization());
+        Self::with_gitlab_url(url, current_version, gitlab_base_url)
+    }
+
+    pub(crate) fn with_gitlab_url(
+        url: &Url,
+        current_version: LenientVersion,
+        gitlab_base_url: String,
+    ) -> Result<Self> {
+        let segments = url
+            .path_segments()
+            .ok_or_else(|| eyre!("failed to get project from {:?}", &url))?
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        // Projects can live in nested groups, everything before the `/-/` separator is part of
+        // the project path, otherwise assume the plain `group/project` form.
+        let project_segments = match segments.iter().position(|s| *s == "-") {
+            Some(separator) => &segments[..separator],
+            None => &segments[..segments.len().min(2)],
+        };
+        if project_segments.len() < 2 {
+            return Err(eyre!("failed to get project from {:?}", &url));
+        }
+
+        Ok(Self {
+            gitlab_base_url,
+            web_base_url: url.origin().ascii_serialization(),
+            project_path: project_segments.join("/"),
+            tags: TagMapper::default(),
+            current_version,
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    fn project_url(&self) -> String {
+        let project_id: String =
+            form_urlencoded::byte_serialize(self.project_path.as_bytes()).collect();
+        format!("{}/projects/{}", self.gitlab_base_url, project_id)
+    }
+
+    fn project_name(&self) -> &str {
+        self.project_path
+            .rsplit('/')
+            .next()
+            .unwrap_or(&self.project_path)
+    }
+
+    /// Reads versions out of tags with `tags` instead of parsing them as they are.
+    pub fn with_tags(mut self, tags: TagMapper) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let releases_url = format!("{}/releases?per_page={}", self.project_url(), PER_PAGE);
+        let releases: Vec<Release> = get_paginated(releases_url).await?;
+        let mut latest_version: Option<LenientVersion> = None;
+        let mut download_url: Option<String> = None;
+
+        debug!("found {} releases", releases.len());
+
+        for release in releases.iter().filter(|r| !r.upcoming_release) {
+            if let Some(tag_name) = self.tags.version(&release.tag_name).as_ref() {
+                debug!("checking tag {}", tag_name);
+                let file_name = file_template.replace(VERSION_PLACEHOLDER, &tag_name.to_string());
+                let asset_urls = release
+                    .assets
+                    .links
+                    .iter()
+                    .map(|link| link.url.as_str())
+                    .chain(release.assets.sources.iter().map(|s| s.url.as_str()));
+
+                for asset_url in asset_urls {
+                    if url_file_name(asset_url) != file_name {
+                        continue;
+                    }
+                    if latest_version.as_ref().is_none_or(|l| tag_name > l) {
+                        latest_version = Some(tag_name.clone());
+                        download_url = Some(asset_url.to_string());
+                    }
+                }
+            }
+        }
+
+        if let Some(latest_version) = latest_version {
+            self.remote_version = Some(latest_version);
+            self.remote_url = download_url;
+            return Ok(());
+        }
+
+        debug!("Falling back to tags as no releases lead to a newer version");
+        let tags_url = format!(
+            "{}/repository/tags?per_page={}",
+            self.project_url(),
+            PER_PAGE
+        );
+        let tags: Vec<Tag> = get_paginated(tags_url).await?;
+        debug!("found {} tags", tags.len());
+
+        for tag in &tags {
+            if let Some(tag_name) = self.tags.version(&tag.name).as_ref() {
+                debug!("checking tag {}", tag_name);
+                if latest_version.as_ref().is_none_or(|l| tag_name > l) {
+                    latest_version = Some(tag_name.clone());
+                    download_url = Some(tag.get_download_url(self));
+                }
+            }
+        }
+
+        if let Some(latest_version) = latest_version {
+            self.remote_version = Some(latest_version);
+            self.remote_url = download_url;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    upcoming_release: bool,
+    assets: Assets,
+}
+
+#[derive(Debug, Deserialize)]
+struct Assets {
+    #[serde(default)]
+    links: Vec<Link>,
+    #[serde(default)]
+    sources: Vec<Source>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Source {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+}
+
+impl Tag {
+    fn get_download_url(&self, gitlab: &GitLab) -> String {
+        format!(
+            "{}/{}/-/archive/{}/{}-{}.tar.gz",
+            gitlab.web_base_url,
+            gitlab.project_path,
+            self.name,
+            gitlab.project_name(),
+            self.name
+        )
+    }
+}
+
+#[async_trait]
+impl VersionCheck for GitLab {
+    fn checker_name(&self) -> &'static str {
+        "gitlab"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{GitLab, VersionCheck};
+    use crate::version::LenientVersion;
+    use crate::{package::VERSION_PLACEHOLDER, setup_error_handlers};
+
+    #[tokio::test]
+    async fn fetch_last_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/projects/group%2Fsub%2Ftool/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "tag_name": "v0.3.0",
+                "upcoming_release": true,
+                "assets": {"links": [], "sources": []}
+            }, {
+                "tag_name": "v0.2.0",
+                "assets": {
+                    "links": [{
+                        "name": "tool-0.2.0-x86_64-linux.tar.gz.sha256",
+                        "url": "https://gitlab.com/group/sub/tool/-/releases/v0.2.0/downloads/tool-0.2.0-x86_64-linux.tar.gz.sha256"
+                    }, {
+                        "name": "tool-0.2.0-x86_64-linux.tar.gz",
+                        "url": "https://gitlab.com/group/sub/tool/-/releases/v0.2.0/downloads/tool-0.2.0-x86_64-linux.tar.gz"
+                    }],
+                    "sources": [{
+                        "format": "tar.gz",
+                        "url": "https://gitlab.com/group/sub/tool/-/archive/v0.2.0/tool-v0.2.0.tar.gz"
+                    }]
+                }
+            }])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut gitlab: Box<dyn VersionCheck> = Box::new(
+            GitLab::with_gitlab_url(
+                &"https://gitlab.com/group/sub/tool/-/releases/v0.1.0/downloads/tool-0.1.0-x86_64-linux.tar.gz"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("0.1.0").unwrap(),
+                mock_server.uri(),
+            )
+            .unwrap(),
+        );
+
+        gitlab
+            .fetch_last_version(&format!("tool-{}-x86_64-linux.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(gitlab.has_newer_version());
+        assert_eq!(
+            gitlab.get_remote_version(),
+            Some(&LenientVersion::parse("0.2.0").unwrap())
+        );
+        assert_eq!(
+            gitlab.get_download_url(),
+            Some("https://gitlab.com/group/sub/tool/-/releases/v0.2.0/downloads/tool-0.2.0-x86_64-linux.tar.gz")
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_last_version_from_tags() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/projects/group%2Ftool/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/projects/group%2Ftool/repository/tags"))
+            .and(query_param("per_page", "100"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        format!(
+                            r#"<{}/projects/group%2Ftool/repository/tags?page=2>; rel="next""#,
+                            mock_server.uri()
+                        )
+                        .as_str(),
+                    )
+                    .set_body_json(json!([{"name": "not-a-version"}, {"name": "v1.0.0"}])),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/projects/group%2Ftool/repository/tags"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"name": "v1.1.0"}])))
+            .mount(&mock_server)
+            .await;
+
+        let mut gitlab = GitLab::with_gitlab_url(
+            &"https://gitlab.com/group/tool/-/archive/v1.0.0/tool-v1.0.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("1.0.0").unwrap(),
+            mock_server.uri(),
+        )
+        .unwrap();
+
+        gitlab
+            .fetch_last_version(&format!("tool-v{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+
+        assert!(gitlab.has_newer_version());
+        assert_eq!(
+            gitlab.get_download_url(),
+            Some("https://gitlab.com/group/tool/-/archive/v1.1.0/tool-v1.1.0.tar.gz")
+        );
+    }
+}
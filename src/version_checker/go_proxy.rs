@@ -0,0 +1,285 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tracing::trace;
+use url::Url;
+
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://proxy.golang.org/"
+        .parse()
+        .expect("error parsing go proxy url");
+}
+
+/// Suffix of major versions >= 2 published by modules without a `go.mod`.
+const INCOMPATIBLE: &str = "+incompatible";
+
+pub struct GoProxy {
+    base_url: Url,
+    current_download_url: Url,
+    current_version: LenientVersion,
+    module_path: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Info {
+    version: String,
+}
+
+impl GoProxy {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        GoProxy::with_proxy_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_proxy_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Modules are downloaded from /<escaped module path>/@v/<version>.zip
+        let module_path = current_download_url
+            .path_segments()
+            .map(|segments| {
+                segments
+                    .take_while(|segment| *segment != "@v")
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .filter(|module_path| !module_path.is_empty())
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get module from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_download_url: current_download_url.clone(),
+            current_version,
+            module_path: unescape_module_path(&module_path),
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let module_url = self
+            .base_url
+            .join(&format!("{}/@v/", escape_module_path(&self.module_path)))
+            .wrap_err("failed to get go proxy url")?;
+        let url = module_url.join("list")?;
+        trace!(message = "fetching go module versions", file_template = file_template, url = %url);
+        let list = CLIENT
+            .get(url)
+            .send()
+            .await
+            .wrap_err("failed to get module versions")?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let mut latest = list
+            .lines()
+            .filter_map(parse_version)
+            .filter(|(version, _)| version.inner().pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        if latest.is_none() {
+            // Modules without tagged releases only have pseudo-versions, which are not listed
+            let url = self.base_url.join(&format!(
+                "{}/@latest",
+                escape_module_path(&self.module_path)
+            ))?;
+            let info: Info = CLIENT
+                .get(url)
+                .send()
+                .await
+                .wrap_err("failed to get latest module version")?
+                .json()
+                .await?;
+            latest = parse_version(&info.version);
+        }
+
+        if let Some((version, raw_version)) = latest {
+            let download_url = self
+                .current_download_url
+                .join(&format!("{}.zip", raw_version))
+                .wrap_err("failed to build download url")?;
+            self.remote_version = Some(version);
+            self.remote_url = Some(download_url.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Parses a module version, `+incompatible` is dropped so it compares like any other release
+/// while the raw value is kept to build urls.
+fn parse_version(raw_version: &str) -> Option<(LenientVersion, String)> {
+    let raw_version = raw_version.trim();
+    let version = raw_version
+        .strip_suffix(INCOMPATIBLE)
+        .unwrap_or(raw_version);
+    Some((
+        LenientVersion::parse(version).ok()?,
+        raw_version.to_string(),
+    ))
+}
+
+/// Go proxies are case insensitive, upper case letters are written as `!` followed by the lower
+/// case letter.
+fn escape_module_path(module_path: &str) -> String {
+    let mut escaped = String::with_capacity(module_path.len());
+    for c in module_path.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn unescape_module_path(escaped: &str) -> String {
+    let mut module_path = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '!' => module_path.extend(chars.next().map(|c| c.to_ascii_uppercase())),
+            c => module_path.push(c),
+        }
+    }
+    module_path
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for GoProxy {
+    fn checker_name(&self) -> &'static str {
+        "go-proxy"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::go_proxy::{escape_module_path, unescape_module_path, GoProxy};
+    use crate::version_checker::VersionCheck;
+
+    #[test]
+    fn test_module_path_escaping() {
+        let module_path = "github.com/BurntSushi/toml";
+        let escaped = escape_module_path(module_path);
+        assert_eq!(escaped, "github.com/!burnt!sushi/toml");
+        assert_eq!(unescape_module_path(&escaped), module_path);
+    }
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/github.com/!burnt!sushi/tool/@v/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "v1.0.0+incompatible\nv2.1.0+incompatible\nv2.2.0-rc.1+incompatible\nv2.0.0+incompatible\n",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut go_proxy: Box<dyn VersionCheck> = Box::new(
+            GoProxy::with_proxy_url(
+                &format!("{}/", mock_server.uri()).parse().unwrap(),
+                &"https://proxy.golang.org/github.com/!burnt!sushi/tool/@v/v2.0.0+incompatible.zip"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("2.0.0").unwrap(),
+            )
+            .unwrap(),
+        );
+        go_proxy
+            .fetch_last_version("v2.0.0+incompatible.zip")
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(go_proxy.has_newer_version());
+        assert_eq!(
+            go_proxy
+                .get_remote_version()
+                .unwrap()
+                .clean_original_value(),
+            "2.1.0"
+        );
+        assert_eq!(
+            go_proxy.get_download_url(),
+            Some(
+                "https://proxy.golang.org/github.com/!burnt!sushi/tool/@v/v2.1.0+incompatible.zip"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_latest_pseudo_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/example.org/tool/@v/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(""))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/example.org/tool/@latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"Version":"v0.0.0-20230501100000-abcdef123456","Time":"2023-05-01T10:00:00Z"}"#,
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut go_proxy = GoProxy::with_proxy_url(
+            &format!("{}/", mock_server.uri()).parse().unwrap(),
+            &"https://proxy.golang.org/example.org/tool/@v/v0.0.0-20230101100000-123456abcdef.zip"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("v0.0.0-20230101100000-123456abcdef").unwrap(),
+        )
+        .unwrap();
+        go_proxy
+            .fetch_last_version("v0.0.0-20230101100000-123456abcdef.zip")
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert_eq!(
+            go_proxy.get_download_url(),
+            Some("https://proxy.golang.org/example.org/tool/@v/v0.0.0-20230501100000-abcdef123456.zip")
+        );
+    }
+}
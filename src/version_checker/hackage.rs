@@ -0,0 +1,178 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use reqwest::header::ACCEPT;
+use serde::Deserialize;
+use tracing::trace;
+use url::Url;
+
+use crate::package::VERSION_PLACEHOLDER;
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://hackage.haskell.org/package/"
+        .parse()
+        .expect("error parsing hackage url");
+}
+
+pub struct Hackage {
+    base_url: Url,
+    current_download_url: Url,
+    current_version: LenientVersion,
+    package_name: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Preferred {
+    #[serde(rename = "normal-version", default)]
+    normal_version: Vec<String>,
+}
+
+impl Hackage {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        Hackage::with_hackage_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_hackage_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Packages are downloaded from /package/<name>-<version>/<name>-<version>.tar.gz
+        let package_name = current_download_url
+            .path_segments()
+            .and_then(|mut segments| segments.nth(1))
+            .and_then(|package_id| {
+                package_id.strip_suffix(&format!("-{}", current_version.original_value()))
+            })
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get package from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_download_url: current_download_url.clone(),
+            current_version,
+            package_name: package_name.to_string(),
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("{}/preferred", self.package_name))
+            .wrap_err("failed to get hackage url")?;
+        trace!(message = "fetching hackage version", file_template = file_template, url = %url);
+        let response = CLIENT
+            .get(url)
+            .header(ACCEPT, "application/json")
+            .send()
+            .await
+            .wrap_err("failed to get latest version")?
+            .error_for_status()?;
+        let preferred: Preferred = response.json().await?;
+
+        // Deprecated versions are listed apart, so every normal version is a candidate
+        let latest = preferred
+            .normal_version
+            .iter()
+            .filter_map(|v| Some((LenientVersion::parse(v).ok()?, v)))
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        if let Some((version, number)) = latest {
+            let file_name = file_template.replace(VERSION_PLACEHOLDER, number);
+            let download_url = self
+                .current_download_url
+                .join(&format!(
+                    "../{}-{}/{}",
+                    self.package_name, number, file_name
+                ))
+                .wrap_err("failed to build download url")?;
+            self.remote_version = Some(version);
+            self.remote_url = Some(download_url.to_string());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for Hackage {
+    fn checker_name(&self) -> &'static str {
+        "hackage"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::package::VERSION_PLACEHOLDER;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::hackage::Hackage;
+    use crate::version_checker::VersionCheck;
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/package/pandoc-types/preferred"))
+            .and(header("Accept", "application/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "normal-version": ["1.23.1", "1.23", "1.22.2.1"],
+                "deprecated-version": ["1.24"]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut hackage: Box<dyn VersionCheck> = Box::new(
+            Hackage::with_hackage_url(
+                &format!("{}/package/", mock_server.uri()).parse().unwrap(),
+                &"https://hackage.haskell.org/package/pandoc-types-1.23/pandoc-types-1.23.tar.gz"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("1.23").unwrap(),
+            )
+            .unwrap(),
+        );
+        hackage
+            .fetch_last_version(&format!("pandoc-types-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(hackage.has_newer_version());
+        assert_eq!(
+            hackage.get_download_url(),
+            Some("https://hackage.haskell.org/package/pandoc-types-1.23.1/pandoc-types-1.23.1.tar.gz")
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use tracing::trace;
+use url::Url;
+
+use crate::package::VERSION_PLACEHOLDER;
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://repo1.maven.org/maven2/"
+        .parse()
+        .expect("error parsing maven central url");
+}
+
+const SNAPSHOT: &str = "-SNAPSHOT";
+
+pub struct Maven {
+    base_url: Url,
+    current_version: LenientVersion,
+    /// Group path and artifact id, e.g. `org/apache/maven/maven-core`
+    artifact_path: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl Maven {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        Maven::with_maven_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_maven_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Artifacts are downloaded from /maven2/<group path>/<artifact>/<version>/<file>
+        let artifact_path = current_download_url
+            .path_segments()
+            .map(|segments| segments.skip(1).collect::<Vec<_>>())
+            .filter(|segments| segments.len() > 3)
+            .map(|segments| segments[..segments.len() - 2].join("/"))
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get artifact from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_version,
+            artifact_path,
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("{}/maven-metadata.xml", self.artifact_path))
+            .wrap_err("failed to get maven metadata url")?;
+        trace!(message = "fetching maven metadata", file_template = file_template, url = %url);
+        let metadata = CLIENT
+            .get(url)
+            .send()
+            .await
+            .wrap_err("failed to get maven metadata")?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        if let Some((version, number)) = latest_version(&metadata)? {
+            let file_name = file_template.replace(VERSION_PLACEHOLDER, &number);
+            let download_url = self
+                .base_url
+                .join(&format!("{}/{}/{}", self.artifact_path, number, file_name))
+                .wrap_err("failed to build download url")?;
+            self.remote_version = Some(version);
+            self.remote_url = Some(download_url.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Picks `<release>` from the metadata, falling back to the highest listed `<version>`.
+fn latest_version(metadata: &str) -> Result<Option<(LenientVersion, String)>> {
+    let document =
+        roxmltree::Document::parse(metadata).wrap_err("failed to parse maven metadata")?;
+    let versioning = match document
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name("versioning"))
+    {
+        Some(versioning) => versioning,
+        None => return Ok(None),
+    };
+    let parse = |number: &str| {
+        if number.ends_with(SNAPSHOT) {
+            return None;
+        }
+        Some((LenientVersion::parse(number).ok()?, number.to_string()))
+    };
+
+    let release = versioning
+        .children()
+        .find(|node| node.has_tag_name("release"))
+        .and_then(|node| node.text())
+        .and_then(|number| parse(number.trim()));
+    if release.is_some() {
+        return Ok(release);
+    }
+
+    Ok(versioning
+        .descendants()
+        .filter(|node| node.has_tag_name("version"))
+        .filter_map(|node| parse(node.text()?.trim()))
+        .max_by(|(a, _), (b, _)| a.cmp(b)))
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for Maven {
+    fn checker_name(&self) -> &'static str {
+        "maven"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::package::VERSION_PLACEHOLDER;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::maven::{latest_version, Maven};
+    use crate::version_checker::VersionCheck;
+
+    const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<metadata>
+  <groupId>org.example.tools</groupId>
+  <artifactId>tool-cli</artifactId>
+  <versioning>
+    <latest>2.1.0-SNAPSHOT</latest>
+    <versions>
+      <version>1.9.0</version>
+      <version>2.0.0</version>
+      <version>2.1.0-SNAPSHOT</version>
+    </versions>
+    <lastUpdated>20230501100000</lastUpdated>
+  </versioning>
+</metadata>"#;
+
+    #[test]
+    fn test_latest_version_prefers_release() {
+        let metadata = METADATA.replace("<versions>", "<release>1.9.0</release><versions>");
+        let (version, _) = latest_version(&metadata).unwrap().unwrap();
+        assert_eq!(version.original_value(), "1.9.0");
+    }
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/maven2/org/example/tools/tool-cli/maven-metadata.xml",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_string(METADATA))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut maven: Box<dyn VersionCheck> = Box::new(
+            Maven::with_maven_url(
+                &format!("{}/maven2/", mock_server.uri()).parse().unwrap(),
+                &"https://repo1.maven.org/maven2/org/example/tools/tool-cli/1.9.0/tool-cli-1.9.0-all.jar"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("1.9.0").unwrap(),
+            )
+            .unwrap(),
+        );
+        maven
+            .fetch_last_version(&format!("tool-cli-{}-all.jar", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(maven.has_newer_version());
+        assert_eq!(
+            maven.get_download_url(),
+            Some(
+                format!(
+                    "{}/maven2/org/example/tools/tool-cli/2.0.0/tool-cli-2.0.0-all.jar",
+                    mock_server.uri()
+                )
+                .as_str()
+            )
+        );
+    }
+}
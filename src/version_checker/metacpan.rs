@@ -0,0 +1,177 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tracing::trace;
+use url::Url;
+
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://fastapi.metacpan.org/v1/"
+        .parse()
+        .expect("error parsing metacpan url");
+}
+
+pub struct MetaCpan {
+    base_url: Url,
+    current_version: LenientVersion,
+    distribution: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+    remote_sha256: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    version: String,
+    download_url: String,
+    maturity: String,
+    checksum_sha256: Option<String>,
+}
+
+impl MetaCpan {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        MetaCpan::with_metacpan_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_metacpan_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Releases are downloaded from /authors/id/<A>/<AU>/<AUTHOR>/<Distribution>-<version>.tar.gz
+        let distribution = current_download_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|file_name| file_name.rsplit_once('-'))
+            .map(|(distribution, _)| distribution)
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get distribution from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_version,
+            distribution: distribution.to_string(),
+            remote_version: None,
+            remote_url: None,
+            remote_sha256: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("release/{}", self.distribution))
+            .wrap_err("failed to get metacpan url")?;
+        trace!(message = "fetching metacpan version", file_template = file_template, url = %url);
+        let response = CLIENT
+            .get(url)
+            .send()
+            .await
+            .wrap_err("failed to get latest version")?
+            .error_for_status()?;
+        let release: Release = response.json().await?;
+
+        // Developer releases are only returned when no stable release exists
+        if release.maturity != "released" {
+            return Ok(());
+        }
+        let version = LenientVersion::parse(&release.version)
+            .map_err(|e| eyre!("failed to parse version {:?} {:?}", release.version, e))?;
+        self.remote_version = Some(version);
+        self.remote_url = Some(release.download_url);
+        self.remote_sha256 = release.checksum_sha256;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for MetaCpan {
+    fn checker_name(&self) -> &'static str {
+        "metacpan"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+
+    fn get_remote_sha256(&self) -> Option<&str> {
+        self.remote_sha256.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::package::VERSION_PLACEHOLDER;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::metacpan::MetaCpan;
+    use crate::version_checker::VersionCheck;
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/release/Try-Tiny"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "distribution": "Try-Tiny",
+                "version": "0.31",
+                "maturity": "released",
+                "status": "latest",
+                "download_url": "https://cpan.metacpan.org/authors/id/E/ET/ETHER/Try-Tiny-0.31.tar.gz",
+                "checksum_sha256": "3300d31d8a4075b26d8f46ce864a1d913e0e8467ceeba6655d5d2b2e206c11be"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut metacpan: Box<dyn VersionCheck> = Box::new(
+            MetaCpan::with_metacpan_url(
+                &format!("{}/v1/", mock_server.uri()).parse().unwrap(),
+                &"https://cpan.metacpan.org/authors/id/E/ET/ETHER/Try-Tiny-0.30.tar.gz"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("0.30").unwrap(),
+            )
+            .unwrap(),
+        );
+        metacpan
+            .fetch_last_version(&format!("Try-Tiny-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(metacpan.has_newer_version());
+        assert_eq!(
+            metacpan.get_download_url(),
+            Some("https://cpan.metacpan.org/authors/id/E/ET/ETHER/Try-Tiny-0.31.tar.gz")
+        );
+        assert_eq!(
+            metacpan.get_remote_sha256(),
+            Some("3300d31d8a4075b26d8f46ce864a1d913e0e8467ceeba6655d5d2b2e206c11be")
+        );
+    }
+}
@@ -1,19 +1,49 @@
+pub mod cran;
+pub mod crates_io;
+pub mod endpoint;
+pub mod feed;
+pub mod git;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
+pub mod go_proxy;
+pub mod hackage;
+pub mod maven;
+pub mod metacpan;
+pub mod npm;
+pub mod open_vsx;
+pub mod plugin;
 pub mod pypi;
+pub mod redirect;
+mod registry;
+pub mod rubygems;
+pub mod sourceforge;
+pub mod tag_mapping;
+pub mod web_page;
 
+use crate::config::{Config, PackageConfig};
+use crate::package::VERSION_PLACEHOLDER;
 use crate::version::LenientVersion;
-use crate::version_checker::pypi::PyPi;
+use crate::CLIENT;
 use async_trait::async_trait;
 use color_eyre::{
     eyre::{eyre, WrapErr},
     Result,
 };
 
+use regex::Regex;
+use reqwest::header::{HeaderMap, LINK};
+use serde::de::DeserializeOwned;
+use tracing::debug;
 use url::Url;
 
-use self::github::Github;
-#[cfg(test)]
-use self::tests::TestServer;
+use self::endpoint::Endpoint;
+use self::feed::Feed;
+use self::git::Git;
+use self::plugin::Plugin;
+use self::redirect::Redirect;
+use self::tag_mapping::TagMapper;
+use self::web_page::WebPage;
 
 #[async_trait]
 pub trait VersionCheck {
@@ -29,51 +59,206 @@ pub trait VersionCheck {
     }
 
     fn get_download_url(&self) -> Option<&str>;
+
+    /// Subresource integrity string (e.g. `sha512-<base64>`) published by the upstream for the
+    /// download url, when it provides one.
+    fn get_remote_integrity(&self) -> Option<&str> {
+        None
+    }
+
+    /// Hex encoded sha256 published by the upstream for the download url, the downloaded file is
+    /// rejected when it does not match.
+    fn get_remote_sha256(&self) -> Option<&str> {
+        None
+    }
 }
 
 pub fn get_version_checker(
     url: &str,
     current_version: LenientVersion,
+    config: &Config,
+    package_name: &str,
 ) -> Result<Box<dyn VersionCheck>> {
     let parsed_url = Url::parse(url).wrap_err_with(|| format!("failed to parse url {:?}", url))?;
-    get_version_checker_from_parsed_url(&parsed_url, current_version)
+    get_version_checker_from_parsed_url(&parsed_url, current_version, config, package_name)
         .wrap_err_with(|| format!("failed to find a checker for url {:?}", url))
 }
 
 fn get_version_checker_from_parsed_url(
     url: &Url,
     current_version: LenientVersion,
+    config: &Config,
+    package_name: &str,
 ) -> Result<Box<dyn VersionCheck>> {
-    match url.domain() {
-        Some("github.com") => Ok(Box::new(Github::new(url, current_version)?)),
-        #[cfg(test)]
-        Some("aur-test.localtest.me") => Ok(Box::new(TestServer::new())),
-        Some("files.pythonhosted.org") => Ok(Box::new(PyPi::new(url, current_version)?)),
-        e => Err(eyre!(
-            "version checker not implemented for domain {:?} yet",
-            e
-        )),
+    let default_package_config = PackageConfig::default();
+    let package_config = config
+        .package(package_name)
+        .unwrap_or(&default_package_config);
+    if let Some(name) = &package_config.checker {
+        let registration =
+            registry::find_by_name(name).ok_or_else(|| eyre!("unknown checker {:?}", name))?;
+        let checker_url = match &package_config.checker_url {
+            Some(checker_url) => Url::parse(checker_url)
+                .wrap_err_with(|| format!("failed to parse checker url {:?}", checker_url))?,
+            None => url.clone(),
+        };
+        return registration.build(&checker_url, current_version, config, package_config);
+    }
+    if let Some(git) = &package_config.git {
+        return Ok(Box::new(
+            Git::new(url.as_str(), current_version, git)
+                .with_tags(TagMapper::new(package_config.tag.as_ref())?),
+        ));
+    }
+    if let Some(endpoint) = &package_config.endpoint {
+        return Ok(Box::new(
+            Endpoint::new(url.as_str(), current_version, endpoint)?
+                .with_tags(TagMapper::new(package_config.tag.as_ref())?),
+        ));
+    }
+    if let Some(feed) = &package_config.feed {
+        return Ok(Box::new(
+            Feed::new(url.as_str(), current_version, feed)?
+                .with_tags(TagMapper::new(package_config.tag.as_ref())?),
+        ));
+    }
+    if let Some(redirect) = &package_config.redirect {
+        return Ok(Box::new(Redirect::new(current_version, redirect)?));
+    }
+    if let Some(web_page) = &package_config.web_page {
+        return Ok(Box::new(WebPage::new(url, current_version, web_page)?));
+    }
+
+    match registry::find_for_url(url, config) {
+        Some(registration) => registration.build(url, current_version, config, package_config),
+        None => match url
+            .host_str()
+            .and_then(|host| Plugin::find(config.plugin_dir(), host))
+        {
+            Some(executable) => Ok(Box::new(Plugin::new(
+                executable,
+                url.as_str(),
+                current_version,
+            ))),
+            None => Err(eyre!(
+                "version checker not implemented for domain {:?} yet",
+                url.domain()
+            )),
+        },
     }
 }
 
-fn get_new_version_filename(
-    file_template: &str,
-    current_version: &LenientVersion,
-    new_version: &LenientVersion,
-) -> String {
-    file_template.replace(
-        current_version.original_value(),
-        new_version.original_value(),
-    )
+/// Expands the `{version}` and `{tag}` placeholders of a user supplied url template.
+fn expand_url_template(template: &str, version: &LenientVersion, tags: &TagMapper) -> String {
+    template
+        .replace("{version}", version.clean_original_value())
+        .replace("{tag}", &tags.tag(version))
+}
+
+/// Url template of configured checkers when none is given, the current download url with the
+/// last occurrence of its version replaced, which is the one in the file name when it has it.
+fn default_url_template(current_download_url: &str, current_version: &LenientVersion) -> String {
+    let version = current_version.clean_original_value();
+    match current_download_url.rfind(version) {
+        Some(start) => format!(
+            "{}{{version}}{}",
+            &current_download_url[..start],
+            &current_download_url[start + version.len()..]
+        ),
+        None => current_download_url.to_string(),
+    }
+}
+
+/// Builds an anchored regex out of a file template, the version placeholder becomes a `version`
+/// named group.
+fn file_template_regex(file_template: &str) -> Result<Regex> {
+    let escaped = regex::escape(file_template);
+    if !escaped.contains(VERSION_PLACEHOLDER) {
+        return Err(eyre!(
+            "file template {:?} does not contain the version",
+            file_template
+        ));
+    }
+    let pattern = escaped.replacen(
+        VERSION_PLACEHOLDER,
+        r"(?P<version>[0-9][0-9A-Za-z.+~_-]*?)",
+        1,
+    );
+    Ok(Regex::new(&format!("^{}$", pattern))?)
+}
+
+/// Compiles a user supplied regex, making sure it has a `version` named group.
+fn version_pattern(pattern: &str) -> Result<Regex> {
+    let regex = Regex::new(pattern).wrap_err_with(|| format!("invalid pattern {:?}", pattern))?;
+    if regex
+        .capture_names()
+        .flatten()
+        .all(|name| name != "version")
+    {
+        return Err(eyre!("pattern {:?} has no version group", pattern));
+    }
+    Ok(regex)
+}
+
+/// Most pages read out of paginated GitLab and Gitea listings.
+const MAX_PAGES: usize = 10;
+
+/// Reads a JSON listing page by page, following the `Link` header until [`MAX_PAGES`] pages
+/// were read.
+async fn get_paginated<T: DeserializeOwned>(first_page: String) -> Result<Vec<T>> {
+    let mut items = vec![];
+    let mut next = Some(first_page);
+    let mut pages = 0;
+    while let Some(url) = next.take() {
+        if pages == MAX_PAGES {
+            debug!("stopping after {} pages", pages);
+            break;
+        }
+        pages += 1;
+        let response = CLIENT.get(&url).send().await?.error_for_status()?;
+        next = next_link(response.headers());
+        let page: Vec<T> = response.json().await?;
+        items.extend(page);
+    }
+    Ok(items)
+}
+
+/// Extracts the `rel="next"` url out of a `Link` header.
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(LINK)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .find_map(|link| {
+            let (url, params) = link.split_once(';')?;
+            params
+                .split(';')
+                .any(|param| param.trim() == r#"rel="next""#)
+                .then(|| {
+                    url.trim()
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string()
+                })
+        })
+}
+
+/// Last path segment of a url, without its query or fragment.
+fn url_file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::config::Config;
     use crate::version::LenientVersion;
     use async_trait::async_trait;
     use color_eyre::Result;
 
-    use super::{get_version_checker, VersionCheck};
+    use super::{default_url_template, file_template_regex, get_version_checker, VersionCheck};
+    use crate::package::VERSION_PLACEHOLDER;
 
     pub struct TestServer {
         current_version: LenientVersion,
@@ -119,8 +304,196 @@ mod tests {
         let github = get_version_checker(
             "https://github.com/jaysonsantos/mambembe",
             LenientVersion::parse("1.2.0").unwrap(),
+            &Config::default(),
+            "package",
         )
         .unwrap();
         assert_eq!(github.checker_name(), "github");
+
+        let gitlab = get_version_checker(
+            "https://gitlab.com/group/tool/-/archive/v1.2.0/tool-v1.2.0.tar.gz",
+            LenientVersion::parse("1.2.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(gitlab.checker_name(), "gitlab");
+
+        let gitea = get_version_checker(
+            "https://codeberg.org/owner/tool/archive/v1.2.0.tar.gz",
+            LenientVersion::parse("1.2.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(gitea.checker_name(), "gitea");
+
+        let crates_io = get_version_checker(
+            "https://static.crates.io/crates/mambembe-cli/mambembe-cli-0.1.0.crate",
+            LenientVersion::parse("0.1.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(crates_io.checker_name(), "crates-io");
+
+        let npm = get_version_checker(
+            "https://registry.npmjs.org/@scope/tool/-/tool-1.0.0.tgz",
+            LenientVersion::parse("1.0.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(npm.checker_name(), "npm");
+
+        let rubygems = get_version_checker(
+            "https://rubygems.org/downloads/rubocop-ast-1.28.0.gem",
+            LenientVersion::parse("1.28.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(rubygems.checker_name(), "rubygems");
+
+        let go_proxy = get_version_checker(
+            "https://proxy.golang.org/github.com/!burnt!sushi/toml/@v/v1.2.0.zip",
+            LenientVersion::parse("1.2.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(go_proxy.checker_name(), "go-proxy");
+
+        let hackage = get_version_checker(
+            "https://hackage.haskell.org/package/pandoc-types-1.23/pandoc-types-1.23.tar.gz",
+            LenientVersion::parse("1.23").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(hackage.checker_name(), "hackage");
+
+        let metacpan = get_version_checker(
+            "https://cpan.metacpan.org/authors/id/E/ET/ETHER/Try-Tiny-0.31.tar.gz",
+            LenientVersion::parse("0.31").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(metacpan.checker_name(), "metacpan");
+
+        let cran = get_version_checker(
+            "https://cran.r-project.org/src/contrib/sp_2.0-1.tar.gz",
+            LenientVersion::parse("2.0.1").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(cran.checker_name(), "cran");
+
+        let maven = get_version_checker(
+            "https://repo1.maven.org/maven2/org/example/tool-cli/1.9.0/tool-cli-1.9.0.jar",
+            LenientVersion::parse("1.9.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(maven.checker_name(), "maven");
+
+        let sourceforge = get_version_checker(
+            "https://netcologne.dl.sourceforge.net/project/tool/tool/1.4.1/tool-1.4.1.tar.gz",
+            LenientVersion::parse("1.4.1").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(sourceforge.checker_name(), "sourceforge");
+
+        let open_vsx = get_version_checker(
+            "https://open-vsx.org/api/ns/ext/1.0.0/file/ns.ext-1.0.0.vsix",
+            LenientVersion::parse("1.0.0").unwrap(),
+            &Config::default(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(open_vsx.checker_name(), "open-vsx");
+
+        let config = Config::parse(
+            r#"
+            [checkers.github]
+            domains = ["github.example.org"]
+            "#,
+        )
+        .unwrap();
+        let gitlab_host = get_version_checker(
+            "https://gitlab.example.org/group/tool/-/archive/v1.2.0/tool-v1.2.0.tar.gz",
+            LenientVersion::parse("1.2.0").unwrap(),
+            &Config::parse(r#"gitlab-hosts = ["gitlab.example.org"]"#).unwrap(),
+            "package",
+        )
+        .unwrap();
+        assert_eq!(gitlab_host.checker_name(), "gitlab");
+
+        let github_domain = get_version_checker(
+            "https://github.example.org/owner/tool/archive/refs/tags/v1.0.0.tar.gz",
+            LenientVersion::parse("1.0.0").unwrap(),
+            &config,
+            "package",
+        )
+        .unwrap();
+        assert_eq!(github_domain.checker_name(), "github");
+
+        let config = Config::parse(
+            r#"
+            [packages.forced]
+            checker = "github"
+            checker-url = "https://github.com/owner/tool"
+            "#,
+        )
+        .unwrap();
+        let cdn_url = "https://objects.githubusercontent.com/github-production-release-asset/1/tool-1.0.0.tar.gz";
+        assert!(get_version_checker(
+            cdn_url,
+            LenientVersion::parse("1.0.0").unwrap(),
+            &config,
+            "package",
+        )
+        .is_err());
+        let forced = get_version_checker(
+            cdn_url,
+            LenientVersion::parse("1.0.0").unwrap(),
+            &config,
+            "forced",
+        )
+        .unwrap();
+        assert_eq!(forced.checker_name(), "github");
+    }
+
+    #[test]
+    fn test_default_url_template() {
+        assert_eq!(
+            default_url_template(
+                "https://example.org/releases/1.2/tool-1.2.tar.gz",
+                &LenientVersion::parse("1.2").unwrap()
+            ),
+            "https://example.org/releases/1.2/tool-{version}.tar.gz"
+        );
+        assert_eq!(
+            default_url_template(
+                "https://example.org/app1/app-v1-linux.zip",
+                &LenientVersion::parse("v1").unwrap()
+            ),
+            "https://example.org/app1/app-v{version}-linux.zip"
+        );
+    }
+
+    #[test]
+    fn test_file_template_regex() {
+        let regex =
+            file_template_regex(&format!("hello-{}-linux.tar.gz", VERSION_PLACEHOLDER)).unwrap();
+        let captures = regex.captures("hello-2.12.1-linux.tar.gz").unwrap();
+        assert_eq!(&captures["version"], "2.12.1");
+        assert!(regex.captures("hello-2.12.1-linux.tar.gz.sig").is_none());
+        assert!(file_template_regex("hello.tar.gz").is_err());
     }
 }
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use reqwest::header::ACCEPT;
+use serde::Deserialize;
+use tracing::trace;
+use url::Url;
+
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://registry.npmjs.org/"
+        .parse()
+        .expect("error parsing npm registry url");
+}
+
+/// Abbreviated metadata, it is much smaller than the full document and has everything needed here
+const ABBREVIATED_METADATA: &str = "application/vnd.npm.install-v1+json";
+
+pub struct Npm {
+    base_url: Url,
+    current_version: LenientVersion,
+    package_name: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+    remote_integrity: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Document {
+    #[serde(rename = "dist-tags")]
+    dist_tags: DistTags,
+    versions: HashMap<String, PackageVersion>,
+}
+
+#[derive(Deserialize)]
+struct DistTags {
+    latest: String,
+}
+
+#[derive(Deserialize)]
+struct PackageVersion {
+    dist: Dist,
+}
+
+#[derive(Deserialize)]
+struct Dist {
+    tarball: String,
+    integrity: Option<String>,
+}
+
+impl Npm {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        Npm::with_registry_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_registry_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Tarballs live at /<name>/-/<file> or /@<scope>/<name>/-/<file> for scoped packages
+        let package_name = current_download_url
+            .path_segments()
+            .map(|segments| {
+                segments
+                    .take_while(|segment| *segment != "-")
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get package from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_version,
+            package_name,
+            remote_version: None,
+            remote_url: None,
+            remote_integrity: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&self.package_name.replace('/', "%2F"))
+            .wrap_err("failed to get npm registry url")?;
+        trace!(message = "fetching npm version", file_template = file_template, url = %url);
+        let response = CLIENT
+            .get(url)
+            .header(ACCEPT, ABBREVIATED_METADATA)
+            .send()
+            .await
+            .wrap_err("failed to get latest version")?
+            .error_for_status()?;
+        let document: Document = response.json().await?;
+
+        let latest = &document.dist_tags.latest;
+        let version = LenientVersion::parse(latest)
+            .map_err(|e| eyre!("failed to parse version {:?} {:?}", latest, e))?;
+        let package_version = document
+            .versions
+            .get(latest)
+            .ok_or_else(|| eyre!("registry has no metadata for version {:?}", latest))?;
+
+        self.remote_version = Some(version);
+        self.remote_url = Some(package_version.dist.tarball.clone());
+        self.remote_integrity = package_version.dist.integrity.clone();
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for Npm {
+    fn checker_name(&self) -> &'static str {
+        "npm"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+
+    fn get_remote_integrity(&self) -> Option<&str> {
+        self.remote_integrity.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::npm::{Npm, ABBREVIATED_METADATA};
+    use crate::version_checker::VersionCheck;
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/@scope%2Ftool"))
+            .and(header("accept", ABBREVIATED_METADATA))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "@scope/tool",
+                "dist-tags": {"latest": "1.1.0", "next": "2.0.0-rc.1"},
+                "versions": {
+                    "1.0.0": {"dist": {"tarball": "https://registry.npmjs.org/@scope/tool/-/tool-1.0.0.tgz"}},
+                    "1.1.0": {"dist": {
+                        "tarball": "https://registry.npmjs.org/@scope/tool/-/tool-1.1.0.tgz",
+                        "integrity": "sha512-abc"
+                    }},
+                    "2.0.0-rc.1": {"dist": {"tarball": "https://registry.npmjs.org/@scope/tool/-/tool-2.0.0-rc.1.tgz"}}
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let current_version = LenientVersion::parse("1.0.0").unwrap();
+        let mut npm: Box<dyn VersionCheck> = Box::new(
+            Npm::with_registry_url(
+                &format!("{}/", mock_server.uri()).parse().unwrap(),
+                &"https://registry.npmjs.org/@scope/tool/-/tool-1.0.0.tgz"
+                    .parse()
+                    .unwrap(),
+                current_version.clone(),
+            )
+            .unwrap(),
+        );
+        npm.fetch_last_version("tool-1.0.0.tgz").await.unwrap();
+        mock_server.verify().await;
+
+        assert!(npm.has_newer_version());
+        assert_eq!(
+            npm.get_remote_version(),
+            Some(&LenientVersion::parse("1.1.0").unwrap())
+        );
+        assert_eq!(
+            npm.get_download_url(),
+            Some("https://registry.npmjs.org/@scope/tool/-/tool-1.1.0.tgz")
+        );
+        assert_eq!(npm.get_remote_integrity(), Some("sha512-abc"));
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tracing::trace;
+use url::Url;
+
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://open-vsx.org/api/"
+        .parse()
+        .expect("error parsing open vsx url");
+}
+
+pub struct OpenVsx {
+    base_url: Url,
+    current_version: LenientVersion,
+    namespace: String,
+    extension: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Extension {
+    version: String,
+    #[serde(default)]
+    pre_release: bool,
+    files: Files,
+    /// Urls of every version's document, along with aliases like `latest`.
+    #[serde(default)]
+    all_versions: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct Files {
+    download: String,
+}
+
+impl OpenVsx {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        OpenVsx::with_open_vsx_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_open_vsx_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Extensions are downloaded from /api/<namespace>/<extension>/<version>/file/<name>.vsix
+        let (namespace, extension) = current_download_url
+            .path_segments()
+            .and_then(|mut segments| {
+                segments.next().filter(|segment| *segment == "api")?;
+                Some((segments.next()?, segments.next()?))
+            })
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get extension from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_version,
+            namespace: namespace.to_string(),
+            extension: extension.to_string(),
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("{}/{}", self.namespace, self.extension))
+            .wrap_err("failed to get open vsx url")?;
+        trace!(message = "fetching open vsx version", file_template = file_template, url = %url);
+        let mut extension = get_extension(url.as_str()).await?;
+
+        if extension.pre_release {
+            extension = match self.latest_stable(&extension.all_versions).await? {
+                Some(stable) => stable,
+                None => return Ok(()),
+            };
+        }
+        let version = LenientVersion::parse(&extension.version)
+            .map_err(|e| eyre!("failed to parse version {:?} {:?}", extension.version, e))?;
+        self.remote_version = Some(version);
+        self.remote_url = Some(extension.files.download);
+        Ok(())
+    }
+
+    /// Newest version not flagged as a prerelease, only versions newer than the current one are
+    /// looked at as each needs a request.
+    async fn latest_stable(
+        &self,
+        all_versions: &HashMap<String, String>,
+    ) -> Result<Option<Extension>> {
+        let mut versions: Vec<_> = all_versions
+            .iter()
+            .filter_map(|(version, url)| Some((LenientVersion::parse(version).ok()?, url)))
+            .filter(|(version, _)| {
+                version.inner().pre.is_empty() && version > &self.current_version
+            })
+            .collect();
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        for (version, url) in versions {
+            let extension = get_extension(url).await?;
+            if !extension.pre_release {
+                return Ok(Some(extension));
+            }
+            trace!("skipping prerelease {}", version);
+        }
+        Ok(None)
+    }
+}
+
+/// Reads the document of an extension, or of one of its versions.
+async fn get_extension(url: &str) -> Result<Extension> {
+    let response = CLIENT
+        .get(url)
+        .send()
+        .await
+        .wrap_err("failed to get latest version")?
+        .error_for_status()?;
+    Ok(response.json().await?)
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for OpenVsx {
+    fn checker_name(&self) -> &'static str {
+        "open-vsx"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::package::VERSION_PLACEHOLDER;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::open_vsx::OpenVsx;
+    use crate::version_checker::VersionCheck;
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/rust-lang/rust-analyzer"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "namespace": "rust-lang",
+                "name": "rust-analyzer",
+                "version": "0.3.1500",
+                "preRelease": false,
+                "files": {
+                    "download": "https://open-vsx.org/api/rust-lang/rust-analyzer/0.3.1500/file/rust-lang.rust-analyzer-0.3.1500.vsix"
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut open_vsx: Box<dyn VersionCheck> = Box::new(
+            OpenVsx::with_open_vsx_url(
+                &format!("{}/api/", mock_server.uri()).parse().unwrap(),
+                &"https://open-vsx.org/api/rust-lang/rust-analyzer/0.3.1400/file/rust-lang.rust-analyzer-0.3.1400.vsix"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("0.3.1400").unwrap(),
+            )
+            .unwrap(),
+        );
+        open_vsx
+            .fetch_last_version(&format!(
+                "rust-lang.rust-analyzer-{}.vsix",
+                VERSION_PLACEHOLDER
+            ))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(open_vsx.has_newer_version());
+        assert_eq!(
+            open_vsx.get_download_url(),
+            Some("https://open-vsx.org/api/rust-lang/rust-analyzer/0.3.1500/file/rust-lang.rust-analyzer-0.3.1500.vsix")
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_stable_versions() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        let version_url = |version: &str| {
+            format!(
+                "{}/api/rust-lang/rust-analyzer/{}",
+                mock_server.uri(),
+                version
+            )
+        };
+        let extension = |version: &str, pre_release: bool| {
+            json!({
+                "version": version,
+                "preRelease": pre_release,
+                "files": {
+                    "download": format!("https://open-vsx.org/api/rust-lang/rust-analyzer/{0}/file/rust-lang.rust-analyzer-{0}.vsix", version)
+                },
+                "allVersions": {
+                    "latest": version_url("0.4.1600"),
+                    "pre-release": version_url("0.4.1600"),
+                    "0.4.1600": version_url("0.4.1600"),
+                    "0.3.1550": version_url("0.3.1550"),
+                    "0.3.1500": version_url("0.3.1500"),
+                    "0.3.1400": version_url("0.3.1400")
+                }
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/api/rust-lang/rust-analyzer"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(extension("0.4.1600", true)))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/rust-lang/rust-analyzer/0.4.1600"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(extension("0.4.1600", true)))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/rust-lang/rust-analyzer/0.3.1550"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(extension("0.3.1550", true)))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/rust-lang/rust-analyzer/0.3.1500"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(extension("0.3.1500", false)))
+            .mount(&mock_server)
+            .await;
+
+        let mut open_vsx = OpenVsx::with_open_vsx_url(
+            &format!("{}/api/", mock_server.uri()).parse().unwrap(),
+            &"https://open-vsx.org/api/rust-lang/rust-analyzer/0.3.1400/file/rust-lang.rust-analyzer-0.3.1400.vsix"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("0.3.1400").unwrap(),
+        )
+        .unwrap();
+        open_vsx
+            .fetch_last_version(&format!(
+                "rust-lang.rust-analyzer-{}.vsix",
+                VERSION_PLACEHOLDER
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            open_vsx.get_remote_version(),
+            Some(&LenientVersion::parse("0.3.1500").unwrap())
+        );
+        assert_eq!(
+            open_vsx.get_download_url(),
+            Some("https://open-vsx.org/api/rust-lang/rust-analyzer/0.3.1500/file/rust-lang.rust-analyzer-0.3.1500.vsix")
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::process::Stdio;
+
+use async_trait::async_trait;
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result, Section, SectionExt,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use tracing::{debug, instrument};
+
+use super::VersionCheck;
+use crate::version::LenientVersion;
+
+/// Checker delegating to an external executable. It gets a [`Request`] as JSON on stdin and must
+/// print a [`Response`] as JSON on stdout.
+#[derive(Debug)]
+pub struct Plugin {
+    executable: Utf8PathBuf,
+    current_download_url: String,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+    remote_sha256: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    current_version: &'a str,
+    url: &'a str,
+    file_template: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    version: String,
+    url: String,
+    sha256: Option<String>,
+}
+
+impl Plugin {
+    /// Looks for an executable named after `host` in `plugin_dir`.
+    pub fn find(plugin_dir: &Utf8Path, host: &str) -> Option<Utf8PathBuf> {
+        let executable = plugin_dir.join(host);
+        executable.is_file().then_some(executable)
+    }
+
+    pub fn new(
+        executable: Utf8PathBuf,
+        current_download_url: &str,
+        current_version: LenientVersion,
+    ) -> Self {
+        Self {
+            executable,
+            current_download_url: current_download_url.to_string(),
+            current_version,
+            remote_version: None,
+            remote_url: None,
+            remote_sha256: None,
+        }
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let request = serde_json::to_vec(&Request {
+            current_version: self.current_version.original_value(),
+            url: &self.current_download_url,
+            file_template,
+        })?;
+        let mut child = Command::new(&self.executable)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .wrap_err_with(|| format!("failed to run plugin {}", self.executable))?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin.write_all(&request).await?;
+        drop(stdin);
+        let output = child.wait_with_output().await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(eyre!("plugin {} failed", self.executable)
+                .section(stderr.to_string().header("Stderr")));
+        }
+
+        let response: Response = serde_json::from_slice(&output.stdout).wrap_err_with(|| {
+            format!(
+                "plugin {} printed an invalid response {:?}",
+                self.executable,
+                String::from_utf8_lossy(&output.stdout)
+            )
+        })?;
+        debug!("plugin found {:?}", response);
+        let version = LenientVersion::parse(&response.version)
+            .map_err(|e| eyre!("failed to parse version {:?} {:?}", response.version, e))?;
+        self.remote_version = Some(version);
+        self.remote_url = Some(response.url);
+        self.remote_sha256 = response.sha256;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VersionCheck for Plugin {
+    fn checker_name(&self) -> &'static str {
+        "plugin"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+
+    fn get_remote_sha256(&self) -> Option<&str> {
+        self.remote_sha256.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use camino::Utf8Path;
+    use tempdir::TempDir;
+
+    use super::{Plugin, VersionCheck};
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+
+    const PLUGIN: &str = r#"#!/bin/sh
+grep -q '"file_template":"tool-_VERSION_PLACEHOLDER_.tar.gz"' || exit 1
+echo '{"version": "1.1.0", "url": "https://example.org/tool-1.1.0.tar.gz", "sha256": "abc"}'
+"#;
+
+    #[tokio::test]
+    async fn fetch_last_version() {
+        setup_error_handlers().ok();
+        let plugin_dir = TempDir::new("aur-autoupdater-plugins").unwrap();
+        let plugin_dir = Utf8Path::from_path(plugin_dir.path()).unwrap();
+        let executable = plugin_dir.join("example.org");
+        std::fs::write(&executable, PLUGIN).unwrap();
+        std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(Plugin::find(plugin_dir, "other.example.org").is_none());
+        let mut plugin: Box<dyn VersionCheck> = Box::new(Plugin::new(
+            Plugin::find(plugin_dir, "example.org").unwrap(),
+            "https://example.org/tool-1.0.0.tar.gz",
+            LenientVersion::parse("1.0.0").unwrap(),
+        ));
+        plugin
+            .fetch_last_version("tool-_VERSION_PLACEHOLDER_.tar.gz")
+            .await
+            .unwrap();
+
+        assert!(plugin.has_newer_version());
+        assert_eq!(
+            plugin.get_download_url(),
+            Some("https://example.org/tool-1.1.0.tar.gz")
+        );
+        assert_eq!(plugin.get_remote_sha256(), Some("abc"));
+    }
+}
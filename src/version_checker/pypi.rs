@@ -3,42 +3,131 @@ use std::collections::HashMap;
 use color_eyre::eyre::{eyre, Context};
 use color_eyre::Result;
 use lazy_static::lazy_static;
+use reqwest::header::ACCEPT;
 use serde::Deserialize;
 use tracing::trace;
 use url::Url;
 
+use crate::package::VERSION_PLACEHOLDER;
 use crate::version::LenientVersion;
-use crate::version_checker::{get_new_version_filename, VersionCheck};
+use crate::version_checker::VersionCheck;
 use crate::CLIENT;
 
 lazy_static! {
-    static ref BASE_URL: Url = "https://pypi.org/pypi/"
+    static ref BASE_URL: Url = "https://pypi.org/simple/"
         .parse()
         .expect("error parsing pypi url");
 }
 
+/// JSON flavour of the simple repository API, see PEP 691.
+const SIMPLE_JSON: &str = "application/vnd.pypi.simple.v1+json";
+
 pub struct PyPi {
     base_url: Url,
     current_version: LenientVersion,
     project_name: String,
     remote_version: Option<LenientVersion>,
     remote_url: Option<String>,
+    remote_sha256: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct Project {
-    releases: HashMap<LenientVersion, Vec<Release>>,
+    files: Vec<File>,
 }
 
 #[derive(Deserialize)]
-struct Release {
+struct File {
     filename: String,
     url: String,
+    #[serde(default)]
+    hashes: HashMap<String, String>,
+    #[serde(default)]
+    yanked: Yanked,
+}
+
+/// Either a flag or the reason the file was yanked for.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Yanked {
+    Flag(bool),
+    Reason(String),
+}
+
+impl Default for Yanked {
+    fn default() -> Self {
+        Yanked::Flag(false)
+    }
+}
+
+/// What a distribution file name is made of, see PEP 427 for wheels and PEP 625 for sdists.
+#[derive(Debug, PartialEq)]
+struct Distribution<'a> {
+    name: String,
+    version: &'a str,
+    kind: Kind<'a>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Kind<'a> {
+    /// Extension of the source archive, `.tar.gz` or `.zip`.
+    Sdist(&'a str),
+    /// Compatibility tags, e.g. `py3-none-any`.
+    Wheel(&'a str),
+}
+
+impl<'a> Distribution<'a> {
+    fn parse(filename: &'a str) -> Option<Self> {
+        if let Some(stem) = filename.strip_suffix(".whl") {
+            let mut parts = stem.splitn(3, '-');
+            let name = parts.next()?;
+            let version = parts.next()?;
+            let tags = parts.next()?;
+            // The optional build tag is the only one starting with a digit
+            let tags = match tags.split_once('-') {
+                Some((build, rest)) if build.starts_with(|c: char| c.is_ascii_digit()) => rest,
+                _ => tags,
+            };
+            return Some(Self {
+                name: normalise_name(name),
+                version,
+                kind: Kind::Wheel(tags),
+            });
+        }
+
+        let extension = [".tar.gz", ".zip"]
+            .iter()
+            .copied()
+            .find(|extension| filename.ends_with(*extension))?;
+        // Older sdists kept dashes in the name, versions never have one
+        let (name, version) = filename[..filename.len() - extension.len()].rsplit_once('-')?;
+        Some(Self {
+            name: normalise_name(name),
+            version,
+            kind: Kind::Sdist(extension),
+        })
+    }
+}
+
+/// Normalises a project name as described in PEP 503, so `ConfigUpdater`, `configupdater` and
+/// `config_updater` can be compared.
+fn normalise_name(name: &str) -> String {
+    let mut normalised = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalised.ends_with('-') {
+                normalised.push('-');
+            }
+        } else {
+            normalised.push(c.to_ascii_lowercase());
+        }
+    }
+    normalised
 }
 
 impl PyPi {
     pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
-        PyPi::with_pypi_url(&*BASE_URL, current_download_url, current_version)
+        PyPi::with_pypi_url(&BASE_URL, current_download_url, current_version)
     }
 
     pub(crate) fn with_pypi_url(
@@ -46,10 +135,13 @@ impl PyPi {
         current_download_url: &Url,
         current_version: LenientVersion,
     ) -> Result<Self> {
+        // Files live under `/packages/source/<letter>/<name>/` or hashed paths, the file name is
+        // the only part always holding the project name
         let project_name = current_download_url
-            .path()
-            .split('/')
-            .nth(4)
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(Distribution::parse)
+            .map(|distribution| distribution.name)
             .ok_or_else(|| {
                 eyre!(
                     "failed to get project from current url {}",
@@ -59,41 +151,75 @@ impl PyPi {
         Ok(Self {
             base_url: base_url.clone(),
             current_version,
-            project_name: project_name.to_string(),
+            project_name,
             remote_version: None,
             remote_url: None,
+            remote_sha256: None,
         })
     }
 
     async fn do_fetch_last_version(&mut self, file_template: &str) -> color_eyre::Result<()> {
         let url = self
             .base_url
-            .join(&format!("{}/json", self.project_name))
+            .join(&format!("{}/", self.project_name))
             .wrap_err("failed to get pypi url")?;
         trace!(message = "fetching pypi version", file_template = file_template, url = %url);
         let response = CLIENT
             .get(url)
+            .header(ACCEPT, SIMPLE_JSON)
             .send()
             .await
-            .wrap_err("failed to get latest version")?;
+            .wrap_err("failed to get latest version")?
+            .error_for_status()?;
         let project: Project = response.json().await?;
 
-        if let Some((version, release)) = project.latest_version() {
-            self.remote_version = Some(version.clone());
-            self.remote_url = self.get_matching_release(release, file_template);
+        let template =
+            file_template.replace(VERSION_PLACEHOLDER, &self.current_version.to_string());
+        let wanted = Distribution::parse(&template)
+            .ok_or_else(|| eyre!("{:?} is not an sdist or a wheel", file_template))?;
+        if let Some((version, file)) = self.latest_file(&project, &wanted) {
+            self.remote_version = Some(version);
+            self.remote_url = Some(file.url.clone());
+            self.remote_sha256 = file.hashes.get("sha256").cloned();
         }
         Ok(())
     }
 
-    fn get_matching_release(&self, releases: &[Release], file_template: &str) -> Option<String> {
-        let remote_version = self.remote_version.as_ref()?;
-        let expected_template =
-            get_new_version_filename(file_template, &self.current_version, remote_version);
-        releases
+    /// Newest stable file of the same kind as `wanted`, yanked files are skipped. Sdists can
+    /// switch between archive formats, the one of `wanted` is preferred for a given version.
+    fn latest_file<'a>(
+        &self,
+        project: &'a Project,
+        wanted: &Distribution,
+    ) -> Option<(LenientVersion, &'a File)> {
+        project
+            .files
             .iter()
-            .filter(|release| release.filename == expected_template)
-            .map(|release| release.url.clone())
-            .next()
+            .filter(|file| match &file.yanked {
+                Yanked::Flag(yanked) => !yanked,
+                Yanked::Reason(reason) => {
+                    trace!("skipping {} yanked because of {:?}", file.filename, reason);
+                    false
+                }
+            })
+            .filter_map(|file| {
+                let distribution = Distribution::parse(&file.filename)?;
+                if distribution.name != self.project_name {
+                    return None;
+                }
+                let preferred = match (&distribution.kind, &wanted.kind) {
+                    (Kind::Sdist(extension), Kind::Sdist(wanted)) => extension == wanted,
+                    (Kind::Wheel(tags), Kind::Wheel(wanted)) if tags == wanted => true,
+                    _ => return None,
+                };
+                let version = LenientVersion::parse(distribution.version).ok()?;
+                Some((version, preferred, file))
+            })
+            .filter(|(version, _, _)| version.inner().pre.is_empty())
+            .max_by(|(a, a_preferred, _), (b, b_preferred, _)| {
+                a.cmp(b).then(a_preferred.cmp(b_preferred))
+            })
+            .map(|(version, _, file)| (version, file))
     }
 }
 
@@ -118,23 +244,9 @@ impl VersionCheck for PyPi {
     fn get_download_url(&self) -> Option<&str> {
         self.remote_url.as_deref()
     }
-}
 
-impl Project {
-    fn latest_version(&self) -> Option<(&LenientVersion, &Vec<Release>)> {
-        let mut versions = self
-            .releases
-            .iter()
-            .filter_map(|(v, r)| {
-                if v.inner().pre.is_empty() {
-                    Some((v, r))
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<(&LenientVersion, &Vec<Release>)>>();
-        versions.sort_by(|(a, _), (b, _)| a.cmp(b));
-        versions.last().cloned()
+    fn get_remote_sha256(&self) -> Option<&str> {
+        self.remote_sha256.as_deref()
     }
 }
 
@@ -143,9 +255,12 @@ mod tests {
     use std::env::set_var;
     use std::fs;
 
-    use wiremock::matchers::{method, path};
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
+    use super::{Distribution, Kind};
+    use crate::package::VERSION_PLACEHOLDER;
     use crate::setup_error_handlers;
     use crate::version::LenientVersion;
     use crate::version_checker::pypi::PyPi;
@@ -155,17 +270,18 @@ mod tests {
     async fn fetch_latest_version() {
         set_var("RUST_LOG", "aur_autoupdater=trace");
         setup_error_handlers().ok();
-        let response = fs::read("tests/fixtures/pypi.json").unwrap();
+        let response = fs::read("tests/fixtures/pypi-simple.json").unwrap();
         let mock_server = MockServer::start().await;
         Mock::given(method("GET"))
-            .and(path("/pypi/ConfigUpdater/json"))
+            .and(path("/simple/configupdater/"))
+            .and(header("Accept", "application/vnd.pypi.simple.v1+json"))
             .respond_with(ResponseTemplate::new(200).set_body_bytes(&*response))
             .expect(1)
             .mount(&mock_server)
             .await;
 
         let current_version = LenientVersion::parse("2.0").unwrap();
-        let base_uri = format!("{}/pypi/", mock_server.uri());
+        let base_uri = format!("{}/simple/", mock_server.uri());
         let download_url = "https://files.pythonhosted.org/packages/source/C/ConfigUpdater/ConfigUpdater-2.0.tar.gz".parse().unwrap();
         let mut pypi: Box<dyn VersionCheck> = Box::new(
             PyPi::with_pypi_url(
@@ -175,7 +291,7 @@ mod tests {
             )
             .unwrap(),
         );
-        pypi.fetch_last_version("ConfigUpdater-2.0.tar.gz")
+        pypi.fetch_last_version(&format!("ConfigUpdater-{}.tar.gz", VERSION_PLACEHOLDER))
             .await
             .unwrap();
 
@@ -185,6 +301,111 @@ mod tests {
         assert_eq!(pypi.get_current_version(), &current_version);
         assert_eq!(pypi.get_remote_version(), Some(&remote_version));
         assert_eq!(pypi.get_download_url(), Some("https://files.pythonhosted.org/packages/9b/0e/0e730b2b3691f8374a74833a48b90616eb4de61f197d924cebd8d2e07d00/ConfigUpdater-3.0.1.tar.gz"));
+        assert_eq!(
+            pypi.get_remote_sha256(),
+            Some("372a6a6ef598a118ec17927bec9486a7d36f44ccd3e641e879e0bf998b70924e")
+        );
         assert!(pypi.has_newer_version());
     }
+
+    #[tokio::test]
+    async fn skips_yanked_and_renamed_files() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        let file = |filename: &str, yanked: serde_json::Value| {
+            json!({
+                "filename": filename,
+                "url": format!("https://files.pythonhosted.org/packages/ab/cd/{}", filename),
+                "hashes": {"sha256": "abc"},
+                "yanked": yanked
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/simple/configupdater/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "meta": {"api-version": "1.1"},
+                "name": "configupdater",
+                "files": [
+                    file("ConfigUpdater-2.0.tar.gz", json!(false)),
+                    file("configupdater-3.1.zip", json!(false)),
+                    file("configupdater-3.1-py3-none-any.whl", json!(false)),
+                    file("configupdater_extras-3.5.tar.gz", json!(false)),
+                    file("configupdater-3.2.tar.gz", json!("broken release")),
+                    file("configupdater-3.2-py3-none-any.whl", json!(true)),
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let mut pypi = PyPi::with_pypi_url(
+            &format!("{}/simple/", mock_server.uri()).parse().unwrap(),
+            &"https://files.pythonhosted.org/packages/source/C/ConfigUpdater/ConfigUpdater-2.0.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("2.0").unwrap(),
+        )
+        .unwrap();
+        pypi.fetch_last_version(&format!("ConfigUpdater-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        assert_eq!(
+            pypi.get_download_url(),
+            Some("https://files.pythonhosted.org/packages/ab/cd/configupdater-3.1.zip")
+        );
+
+        pypi.fetch_last_version(&format!(
+            "ConfigUpdater-{}-py3-none-any.whl",
+            VERSION_PLACEHOLDER
+        ))
+        .await
+        .unwrap();
+        assert_eq!(
+            pypi.get_download_url(),
+            Some(
+                "https://files.pythonhosted.org/packages/ab/cd/configupdater-3.1-py3-none-any.whl"
+            )
+        );
+    }
+
+    #[test]
+    fn project_name_from_download_url() {
+        let project_name = |url: &str| {
+            PyPi::with_pypi_url(
+                &"https://pypi.org/simple/".parse().unwrap(),
+                &url.parse().unwrap(),
+                LenientVersion::parse("2.0").unwrap(),
+            )
+            .map(|pypi| pypi.project_name)
+        };
+        assert_eq!(
+            project_name("https://files.pythonhosted.org/packages/source/C/ConfigUpdater/ConfigUpdater-2.0.tar.gz").unwrap(),
+            "configupdater"
+        );
+        assert_eq!(
+            project_name("https://files.pythonhosted.org/packages/9b/0e/0e730b2b3691f8374a74833a48b90616eb4de61f197d924cebd8d2e07d00/Config_Updater-2.0-py3-none-any.whl").unwrap(),
+            "config-updater"
+        );
+        assert!(project_name("https://files.pythonhosted.org/packages/ab/cd/").is_err());
+    }
+
+    #[test]
+    fn test_parse_distribution() {
+        assert_eq!(
+            Distribution::parse("python-dateutil-2.8.2.tar.gz"),
+            Some(Distribution {
+                name: "python-dateutil".to_string(),
+                version: "2.8.2",
+                kind: Kind::Sdist(".tar.gz"),
+            })
+        );
+        assert_eq!(
+            Distribution::parse("Config_Updater-3.0.1-1-py2.py3-none-any.whl"),
+            Some(Distribution {
+                name: "config-updater".to_string(),
+                version: "3.0.1",
+                kind: Kind::Wheel("py2.py3-none-any"),
+            })
+        );
+        assert_eq!(Distribution::parse("tool-1.0.exe"), None);
+    }
 }
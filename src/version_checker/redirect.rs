@@ -0,0 +1,201 @@
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use regex::Regex;
+use reqwest::header::LOCATION;
+use tracing::{debug, instrument};
+use url::Url;
+
+use super::{file_template_regex, version_pattern, VersionCheck};
+use crate::config::RedirectConfig;
+use crate::version::LenientVersion;
+use crate::NO_REDIRECT_CLIENT;
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Checker for vendors publishing a stable "latest" url which redirects to a versioned file.
+#[derive(Debug)]
+pub struct Redirect {
+    latest_url: Url,
+    pattern: Option<Regex>,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl Redirect {
+    pub fn new(current_version: LenientVersion, config: &RedirectConfig) -> Result<Self> {
+        let latest_url = config
+            .url
+            .parse()
+            .wrap_err_with(|| format!("failed to parse latest url {:?}", config.url))?;
+        let pattern = config.pattern.as_deref().map(version_pattern).transpose()?;
+
+        Ok(Self {
+            latest_url,
+            pattern,
+            current_version,
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        // A configured pattern is matched against the whole url, the file template only against
+        // the file name
+        let (pattern, match_file_name) = match &self.pattern {
+            Some(pattern) => (pattern.clone(), false),
+            None => (file_template_regex(file_template)?, true),
+        };
+        let mut url = self.latest_url.clone();
+
+        for _ in 0..MAX_REDIRECTS {
+            if let Some(version) = extract_version(&url, &pattern, match_file_name) {
+                debug!("found version {} at {}", version, url);
+                self.remote_version = Some(version);
+                self.remote_url = Some(url.to_string());
+                return Ok(());
+            }
+
+            let response = NO_REDIRECT_CLIENT
+                .get(url.clone())
+                .send()
+                .await
+                .wrap_err_with(|| format!("failed to request {}", url))?;
+            if !response.status().is_redirection() {
+                return Err(eyre!(
+                    "{} did not redirect to a versioned url, got status {}",
+                    url,
+                    response.status()
+                ));
+            }
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .ok_or_else(|| eyre!("redirect from {} has no location", url))?
+                .to_str()
+                .wrap_err("location header is not valid text")?;
+            debug!("{} redirects to {}", url, location);
+            url = url
+                .join(location)
+                .wrap_err_with(|| format!("invalid location {:?}", location))?;
+        }
+
+        Err(eyre!(
+            "{} redirected more than {} times without reaching a versioned url",
+            self.latest_url,
+            MAX_REDIRECTS
+        ))
+    }
+}
+
+fn extract_version(url: &Url, pattern: &Regex, match_file_name: bool) -> Option<LenientVersion> {
+    let haystack = if match_file_name {
+        url.path_segments()?.next_back()?
+    } else {
+        url.as_str()
+    };
+    let version = pattern.captures(haystack)?.name("version")?;
+    LenientVersion::parse(version.as_str()).ok()
+}
+
+#[async_trait]
+impl VersionCheck for Redirect {
+    fn checker_name(&self) -> &'static str {
+        "redirect"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{Redirect, VersionCheck};
+    use crate::config::RedirectConfig;
+    use crate::version::LenientVersion;
+    use crate::{package::VERSION_PLACEHOLDER, setup_error_handlers};
+
+    #[tokio::test]
+    async fn fetch_last_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/download/latest"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/mirror/latest"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/mirror/latest"))
+            .respond_with(
+                ResponseTemplate::new(301)
+                    .insert_header("Location", "/files/2023.2/tool-2023.2.1.tar.gz"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let config = RedirectConfig {
+            url: format!("{}/download/latest", mock_server.uri()),
+            pattern: None,
+        };
+        let mut redirect: Box<dyn VersionCheck> =
+            Box::new(Redirect::new(LenientVersion::parse("2023.1").unwrap(), &config).unwrap());
+        redirect
+            .fetch_last_version(&format!("tool-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+
+        assert!(redirect.has_newer_version());
+        assert_eq!(
+            redirect.get_remote_version().unwrap().original_value(),
+            "2023.2.1"
+        );
+        assert_eq!(
+            redirect.get_download_url(),
+            Some(format!("{}/files/2023.2/tool-2023.2.1.tar.gz", mock_server.uri()).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_without_redirect() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/download/latest"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let config = RedirectConfig {
+            url: format!("{}/download/latest", mock_server.uri()),
+            pattern: Some(r"/files/(?P<version>[^/]+)/".to_string()),
+        };
+        let mut redirect =
+            Redirect::new(LenientVersion::parse("2023.1").unwrap(), &config).unwrap();
+        assert!(redirect
+            .fetch_last_version(&format!("tool-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use super::cran::Cran;
+use super::crates_io::CratesIo;
+use super::gitea::Gitea;
+use super::github::Github;
+use super::gitlab::GitLab;
+use super::go_proxy::GoProxy;
+use super::hackage::Hackage;
+use super::maven::Maven;
+use super::metacpan::MetaCpan;
+use super::npm::Npm;
+use super::open_vsx::OpenVsx;
+use super::pypi::PyPi;
+use super::rubygems::RubyGems;
+use super::sourceforge::SourceForge;
+use super::tag_mapping::TagMapper;
+#[cfg(test)]
+use super::tests::TestServer;
+use super::web_page::WebPage;
+use super::VersionCheck;
+use crate::config::{Config, PackageConfig, WebPageConfig};
+use crate::version::LenientVersion;
+
+type Constructor =
+    fn(&Url, LenientVersion, &Config, &PackageConfig) -> Result<Box<dyn VersionCheck>>;
+
+/// Priority of checkers that only scrape generic pages, so any dedicated checker configured for
+/// the same domain wins.
+const GENERIC_PRIORITY: i32 = -10;
+
+/// A checker along with the download urls it can handle.
+pub struct Registration {
+    pub name: &'static str,
+    /// Domain globs, `*` matches any sequence of characters.
+    domains: &'static [&'static str],
+    /// Hosts that are only known from the configuration, like self-hosted forges.
+    configured_hosts: Option<fn(&Config, &str) -> bool>,
+    /// Pattern the url's path has to match on top of the domain.
+    path: Option<Regex>,
+    /// The highest priority wins when several checkers handle a url.
+    priority: i32,
+    constructor: Constructor,
+}
+
+impl Registration {
+    fn new(name: &'static str, domains: &'static [&'static str], constructor: Constructor) -> Self {
+        Self {
+            name,
+            domains,
+            configured_hosts: None,
+            path: None,
+            priority: 0,
+            constructor,
+        }
+    }
+
+    fn with_path(mut self, path: &str) -> Self {
+        self.path = Some(Regex::new(path).expect("invalid checker path pattern"));
+        self
+    }
+
+    fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn with_configured_hosts(mut self, configured_hosts: fn(&Config, &str) -> bool) -> Self {
+        self.configured_hosts = Some(configured_hosts);
+        self
+    }
+
+    pub fn build(
+        &self,
+        url: &Url,
+        current_version: LenientVersion,
+        config: &Config,
+        package: &PackageConfig,
+    ) -> Result<Box<dyn VersionCheck>> {
+        (self.constructor)(url, current_version, config, package)
+    }
+
+    fn priority(&self, config: &Config) -> i32 {
+        config
+            .checker(self.name)
+            .and_then(|checker| checker.priority)
+            .unwrap_or(self.priority)
+    }
+
+    fn handles(&self, url: &Url, config: &Config) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host,
+            None => return false,
+        };
+        let extra_domains = config
+            .checker(self.name)
+            .map(|checker| checker.domains.as_slice())
+            .unwrap_or_default();
+        let domain_matches = self
+            .domains
+            .iter()
+            .copied()
+            .chain(extra_domains.iter().map(String::as_str))
+            .any(|pattern| glob_matches(pattern, host))
+            || self
+                .configured_hosts
+                .is_some_and(|configured_hosts| configured_hosts(config, host));
+
+        domain_matches
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|path| path.is_match(url.path()))
+    }
+}
+
+lazy_static! {
+    static ref REGISTRY: Vec<Registration> = {
+        #[allow(unused_mut)]
+        let mut registry = vec![
+            Registration::new(
+                "github",
+                &["github.com"],
+                |url, version, config, package| {
+                    Ok(Box::new(Github::new(url, version, config, package)?))
+                },
+            )
+            .with_configured_hosts(Config::is_github_host),
+            Registration::new("gitlab", &["gitlab.com"], |url, version, _, package| {
+                Ok(Box::new(
+                    GitLab::new(url, version)?.with_tags(TagMapper::new(package.tag.as_ref())?),
+                ))
+            })
+            .with_configured_hosts(Config::is_gitlab_host),
+            Registration::new("gitea", &[], |url, version, _, package| {
+                Ok(Box::new(
+                    Gitea::new(url, version)?.with_tags(TagMapper::new(package.tag.as_ref())?),
+                ))
+            })
+            .with_configured_hosts(Config::is_gitea_host),
+            Registration::new("pypi", &["files.pythonhosted.org"], |url, version, _, _| {
+                Ok(Box::new(PyPi::new(url, version)?))
+            }),
+            Registration::new("hackage", &["hackage.haskell.org"], |url, version, _, _| {
+                Ok(Box::new(Hackage::new(url, version)?))
+            })
+            .with_path("^/package/"),
+            Registration::new(
+                "metacpan",
+                &["cpan.metacpan.org", "www.cpan.org"],
+                |url, version, _, _| Ok(Box::new(MetaCpan::new(url, version)?)),
+            ),
+            Registration::new(
+                "cran",
+                &["cran.r-project.org", "cloud.r-project.org"],
+                |url, version, _, _| Ok(Box::new(Cran::new(url, version)?)),
+            )
+            .with_path("^/src/contrib/"),
+            Registration::new(
+                "maven",
+                &["repo1.maven.org", "repo.maven.apache.org"],
+                |url, version, _, _| Ok(Box::new(Maven::new(url, version)?)),
+            )
+            .with_path("^/maven2/"),
+            Registration::new("rubygems", &["rubygems.org"], |url, version, _, _| {
+                Ok(Box::new(RubyGems::new(url, version)?))
+            }),
+            Registration::new("go-proxy", &["proxy.golang.org"], |url, version, _, _| {
+                Ok(Box::new(GoProxy::new(url, version)?))
+            })
+            .with_path("/@v/"),
+            Registration::new("open-vsx", &["open-vsx.org"], |url, version, _, _| {
+                Ok(Box::new(OpenVsx::new(url, version)?))
+            })
+            .with_path("^/api/"),
+            Registration::new("npm", &["registry.npmjs.org"], |url, version, _, _| {
+                Ok(Box::new(Npm::new(url, version)?))
+            }),
+            Registration::new(
+                "crates-io",
+                &["static.crates.io", "crates.io"],
+                |url, version, _, _| Ok(Box::new(CratesIo::new(url, version)?)),
+            ),
+            Registration::new(
+                "sourceforge",
+                &[
+                    "downloads.sourceforge.net",
+                    "sourceforge.net",
+                    "*.dl.sourceforge.net",
+                ],
+                |url, version, _, _| Ok(Box::new(SourceForge::new(url, version)?)),
+            ),
+            Registration::new("web-page", &["ftp.gnu.org"], |url, version, _, _| {
+                Ok(Box::new(WebPage::new(
+                    url,
+                    version,
+                    &WebPageConfig::default(),
+                )?))
+            })
+            .with_priority(GENERIC_PRIORITY),
+        ];
+        #[cfg(test)]
+        registry.push(Registration::new(
+            "test-server",
+            &["aur-test.localtest.me"],
+            |_, _, _, _| Ok(Box::new(TestServer::new())),
+        ));
+        registry
+    };
+}
+
+/// Looks a checker up by its name, to force it regardless of the url.
+pub fn find_by_name(name: &str) -> Option<&'static Registration> {
+    REGISTRY
+        .iter()
+        .find(|registration| registration.name == name)
+}
+
+/// Finds the checker with the highest priority handling `url`, ties go to the first name in
+/// alphabetical order.
+pub fn find_for_url(url: &Url, config: &Config) -> Option<&'static Registration> {
+    REGISTRY
+        .iter()
+        .filter(|registration| registration.handles(url, config))
+        .max_by(|a, b| {
+            a.priority(config)
+                .cmp(&b.priority(config))
+                .then_with(|| b.name.cmp(a.name))
+        })
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern.eq_ignore_ascii_case(text),
+        Some((prefix, rest)) => {
+            text.len() >= prefix.len()
+                && text.is_char_boundary(prefix.len())
+                && text[..prefix.len()].eq_ignore_ascii_case(prefix)
+                && (prefix.len()..=text.len())
+                    .filter(|start| text.is_char_boundary(*start))
+                    .any(|start| glob_matches(rest, &text[start..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::{find_for_url, glob_matches};
+    use crate::config::Config;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("github.com", "GitHub.com"));
+        assert!(glob_matches(
+            "*.dl.sourceforge.net",
+            "netcologne.dl.sourceforge.net"
+        ));
+        assert!(!glob_matches("*.dl.sourceforge.net", "dl.sourceforge.net"));
+        assert!(glob_matches("git.*.org", "git.example.org"));
+        assert!(!glob_matches("github.com", "github.com.evil.org"));
+    }
+
+    #[test]
+    fn test_find_for_url() {
+        let find = |url: &str, config: &Config| {
+            find_for_url(&Url::parse(url).unwrap(), config).map(|registration| registration.name)
+        };
+        let config = Config::default();
+        assert_eq!(
+            find("https://ftp.gnu.org/gnu/hello/hello-2.12.tar.gz", &config),
+            Some("web-page")
+        );
+        assert_eq!(find("https://open-vsx.org/extension/ns/ext", &config), None);
+
+        let config = Config::parse(
+            r#"
+            [checkers.gitea]
+            domains = ["ftp.gnu.org"]
+
+            [checkers.github]
+            domains = ["github.example.org"]
+
+            [github-hosts."git.corp.example"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            find("https://ftp.gnu.org/gnu/hello/hello-2.12.tar.gz", &config),
+            Some("gitea")
+        );
+        assert_eq!(
+            find(
+                "https://github.example.org/owner/repo/archive/v1.0.tar.gz",
+                &config
+            ),
+            Some("github")
+        );
+        assert_eq!(
+            find(
+                "https://git.corp.example/owner/repo/archive/v1.0.tar.gz",
+                &config
+            ),
+            Some("github")
+        );
+
+        let config = Config::parse(
+            r#"
+            [checkers.gitea]
+            domains = ["ftp.gnu.org"]
+            priority = -20
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            find("https://ftp.gnu.org/gnu/hello/hello-2.12.tar.gz", &config),
+            Some("web-page")
+        );
+
+        let config = Config::parse(
+            r#"
+            [checkers.gitlab]
+            domains = ["code.example.org"]
+
+            [checkers.github]
+            domains = ["code.example.org"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            find(
+                "https://code.example.org/owner/repo/archive/v1.0.tar.gz",
+                &config
+            ),
+            Some("github")
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tracing::trace;
+use url::Url;
+
+use crate::package::VERSION_PLACEHOLDER;
+use crate::version::LenientVersion;
+use crate::version_checker::VersionCheck;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://rubygems.org/api/v1/"
+        .parse()
+        .expect("error parsing rubygems url");
+}
+
+pub struct RubyGems {
+    base_url: Url,
+    current_download_url: Url,
+    current_version: LenientVersion,
+    gem_name: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+    remote_sha256: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    number: String,
+    prerelease: bool,
+    platform: String,
+    sha: Option<String>,
+}
+
+impl RubyGems {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        RubyGems::with_rubygems_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_rubygems_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Gems are downloaded from /downloads/<gem>-<version>.gem
+        let gem_name = current_download_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|file_name| {
+                file_name.strip_suffix(&format!("-{}.gem", current_version.original_value()))
+            })
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get gem from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_download_url: current_download_url.clone(),
+            current_version,
+            gem_name: gem_name.to_string(),
+            remote_version: None,
+            remote_url: None,
+            remote_sha256: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("versions/{}.json", self.gem_name))
+            .wrap_err("failed to get rubygems url")?;
+        trace!(message = "fetching rubygems version", file_template = file_template, url = %url);
+        let response = CLIENT
+            .get(url)
+            .send()
+            .await
+            .wrap_err("failed to get latest version")?
+            .error_for_status()?;
+        let versions: Vec<Version> = response.json().await?;
+
+        let latest = versions
+            .iter()
+            .filter(|v| !v.prerelease && v.platform == "ruby")
+            .filter_map(|v| Some((LenientVersion::parse(&v.number).ok()?, v)))
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        if let Some((version, release)) = latest {
+            let file_name = file_template.replace(VERSION_PLACEHOLDER, &release.number);
+            let download_url = self
+                .current_download_url
+                .join(&file_name)
+                .wrap_err("failed to build download url")?;
+            self.remote_version = Some(version);
+            self.remote_url = Some(download_url.to_string());
+            self.remote_sha256 = release.sha.clone();
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for RubyGems {
+    fn checker_name(&self) -> &'static str {
+        "rubygems"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+
+    fn get_remote_sha256(&self) -> Option<&str> {
+        self.remote_sha256.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::package::VERSION_PLACEHOLDER;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::rubygems::RubyGems;
+    use crate::version_checker::VersionCheck;
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/versions/rubocop-ast.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"number": "1.30.0.rc1", "prerelease": true, "platform": "ruby", "sha": "aaa"},
+                {"number": "1.29.0", "prerelease": false, "platform": "java", "sha": "bbb"},
+                {"number": "1.28.1", "prerelease": false, "platform": "ruby", "sha": "ccc"},
+                {"number": "1.28.0", "prerelease": false, "platform": "ruby", "sha": "ddd"}
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let current_version = LenientVersion::parse("1.28.0").unwrap();
+        let mut rubygems: Box<dyn VersionCheck> = Box::new(
+            RubyGems::with_rubygems_url(
+                &format!("{}/api/v1/", mock_server.uri()).parse().unwrap(),
+                &"https://rubygems.org/downloads/rubocop-ast-1.28.0.gem"
+                    .parse()
+                    .unwrap(),
+                current_version,
+            )
+            .unwrap(),
+        );
+        rubygems
+            .fetch_last_version(&format!("rubocop-ast-{}.gem", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(rubygems.has_newer_version());
+        assert_eq!(
+            rubygems.get_remote_version(),
+            Some(&LenientVersion::parse("1.28.1").unwrap())
+        );
+        assert_eq!(
+            rubygems.get_download_url(),
+            Some("https://rubygems.org/downloads/rubocop-ast-1.28.1.gem")
+        );
+        assert_eq!(rubygems.get_remote_sha256(), Some("ccc"));
+    }
+}
@@ -0,0 +1,206 @@
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use tracing::{debug, trace};
+use url::Url;
+
+use crate::version::LenientVersion;
+use crate::version_checker::{file_template_regex, VersionCheck};
+use crate::CLIENT;
+
+lazy_static! {
+    static ref BASE_URL: Url = "https://sourceforge.net/projects/"
+        .parse()
+        .expect("error parsing sourceforge url");
+}
+
+const DOWNLOAD_URL: &str = "https://downloads.sourceforge.net/project/";
+
+pub struct SourceForge {
+    base_url: Url,
+    current_version: LenientVersion,
+    project: String,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl SourceForge {
+    pub fn new(current_download_url: &Url, current_version: LenientVersion) -> Result<Self> {
+        SourceForge::with_sourceforge_url(&BASE_URL, current_download_url, current_version)
+    }
+
+    pub(crate) fn with_sourceforge_url(
+        base_url: &Url,
+        current_download_url: &Url,
+        current_version: LenientVersion,
+    ) -> Result<Self> {
+        // Files are downloaded from downloads.sourceforge.net/project/<project>/<path>, the short
+        // downloads.sourceforge.net/<project>/<file>, a mirror or
+        // sourceforge.net/projects/<project>/files/<path>/download
+        let project = current_download_url
+            .path_segments()
+            .and_then(|mut segments| match segments.next()? {
+                "project" | "projects" => segments.next(),
+                project => Some(project),
+            })
+            .filter(|project| !project.is_empty())
+            .ok_or_else(|| {
+                eyre!(
+                    "failed to get project from current url {}",
+                    current_download_url
+                )
+            })?;
+        Ok(Self {
+            base_url: base_url.clone(),
+            current_version,
+            project: project.to_string(),
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("{}/rss?path=/", self.project))
+            .wrap_err("failed to get sourceforge url")?;
+        trace!(message = "fetching sourceforge files", file_template = file_template, url = %url);
+        let body = CLIENT
+            .get(url)
+            .send()
+            .await
+            .wrap_err("failed to get project files")?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let feed = feed_rs::parser::parse(&body[..])
+            .map_err(|e| eyre!("failed to parse files of {}: {}", self.project, e))?;
+        debug!("found {} files", feed.entries.len());
+
+        let pattern = file_template_regex(file_template)?;
+        let latest = feed
+            .entries
+            .iter()
+            .flat_map(|entry| entry.links.iter())
+            .filter_map(|link| self.file_path(&link.href))
+            .filter_map(|file_path| {
+                let file_name = file_path.rsplit('/').next()?;
+                let version = pattern.captures(file_name)?.name("version")?;
+                Some((LenientVersion::parse(version.as_str()).ok()?, file_path))
+            })
+            .filter(|(version, _)| version.inner().pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        if let Some((version, file_path)) = latest {
+            self.remote_version = Some(version);
+            self.remote_url = Some(format!("{}{}/{}", DOWNLOAD_URL, self.project, file_path));
+        }
+        Ok(())
+    }
+
+    /// Extracts the file path out of links like
+    /// `https://sourceforge.net/projects/<project>/files/<path>/download`, which go through the
+    /// mirror selection page.
+    fn file_path(&self, link: &str) -> Option<String> {
+        let link = Url::parse(link).ok()?;
+        let prefix = format!("/projects/{}/files/", self.project);
+        let file_path = link.path().strip_prefix(&prefix)?;
+        let file_path = file_path.strip_suffix("/download").unwrap_or(file_path);
+        Some(file_path.to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl VersionCheck for SourceForge {
+    fn checker_name(&self) -> &'static str {
+        "sourceforge"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::package::VERSION_PLACEHOLDER;
+    use crate::setup_error_handlers;
+    use crate::version::LenientVersion;
+    use crate::version_checker::sourceforge::SourceForge;
+    use crate::version_checker::VersionCheck;
+
+    const FILES_RSS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss xmlns:content="http://purl.org/rss/1.0/modules/content/" version="2.0">
+  <channel>
+    <title>Tool</title>
+    <link>https://sourceforge.net</link>
+    <description>Files from Tool</description>
+    <item>
+      <title><![CDATA[/tool/1.5.0-rc1/tool-1.5.0-rc1.tar.gz]]></title>
+      <link>https://sourceforge.net/projects/tool/files/tool/1.5.0-rc1/tool-1.5.0-rc1.tar.gz/download</link>
+    </item>
+    <item>
+      <title><![CDATA[/tool/1.4.2/tool-1.4.2.tar.gz]]></title>
+      <link>https://sourceforge.net/projects/tool/files/tool/1.4.2/tool-1.4.2.tar.gz/download</link>
+    </item>
+    <item>
+      <title><![CDATA[/tool/1.4.2/tool-1.4.2-win64.zip]]></title>
+      <link>https://sourceforge.net/projects/tool/files/tool/1.4.2/tool-1.4.2-win64.zip/download</link>
+    </item>
+    <item>
+      <title><![CDATA[/tool/1.4.1/tool-1.4.1.tar.gz]]></title>
+      <link>https://sourceforge.net/projects/tool/files/tool/1.4.1/tool-1.4.1.tar.gz/download</link>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[tokio::test]
+    async fn fetch_latest_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/projects/tool/rss"))
+            .and(query_param("path", "/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(FILES_RSS))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut sourceforge: Box<dyn VersionCheck> = Box::new(
+            SourceForge::with_sourceforge_url(
+                &format!("{}/projects/", mock_server.uri()).parse().unwrap(),
+                &"https://downloads.sourceforge.net/project/tool/tool/1.4.1/tool-1.4.1.tar.gz"
+                    .parse()
+                    .unwrap(),
+                LenientVersion::parse("1.4.1").unwrap(),
+            )
+            .unwrap(),
+        );
+        sourceforge
+            .fetch_last_version(&format!("tool-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+        mock_server.verify().await;
+
+        assert!(sourceforge.has_newer_version());
+        assert_eq!(
+            sourceforge.get_download_url(),
+            Some("https://downloads.sourceforge.net/project/tool/tool/1.4.2/tool-1.4.2.tar.gz")
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use color_eyre::{eyre::eyre, Result};
+use regex::Regex;
+
+use super::version_pattern;
+use crate::config::TagMapping;
+use crate::version::LenientVersion;
+
+/// Turns upstream tags like `cli/v1.2.3` or `release-1.2.3` into versions, and versions back
+/// into tags.
+#[derive(Debug, Default)]
+pub struct TagMapper(Mapping);
+
+#[derive(Debug, Default)]
+enum Mapping {
+    /// Tags are versions as they are.
+    #[default]
+    Identity,
+    Prefix(String),
+    Regex {
+        regex: Regex,
+        /// Tag with a `{version}` placeholder, derived from the regex.
+        template: String,
+    },
+}
+
+impl TagMapper {
+    pub fn new(mapping: Option<&TagMapping>) -> Result<Self> {
+        let mapping = match mapping {
+            None => Mapping::Identity,
+            Some(TagMapping::Prefix(prefix)) => Mapping::Prefix(prefix.clone()),
+            Some(TagMapping::Regex(pattern)) => {
+                let template = reverse_template(pattern).ok_or_else(|| {
+                    eyre!(
+                        "tag pattern {:?} can only have literal text around its version group",
+                        pattern
+                    )
+                })?;
+                Mapping::Regex {
+                    regex: version_pattern(&format!("^(?:{})$", pattern))?,
+                    template,
+                }
+            }
+        };
+        Ok(Self(mapping))
+    }
+
+    /// Version of `tag`, if it is a version tag at all.
+    pub fn version(&self, tag: &str) -> Option<LenientVersion> {
+        let version = match &self.0 {
+            Mapping::Identity => tag,
+            Mapping::Prefix(prefix) => tag.strip_prefix(prefix.as_str())?,
+            Mapping::Regex { regex, .. } => regex.captures(tag)?.name("version")?.as_str(),
+        };
+        LenientVersion::parse(version).ok()
+    }
+
+    /// Tag of a version returned by [`TagMapper::version`].
+    pub fn tag(&self, version: &LenientVersion) -> String {
+        match &self.0 {
+            Mapping::Identity => version.original_value().to_string(),
+            Mapping::Prefix(prefix) => format!("{}{}", prefix, version.original_value()),
+            Mapping::Regex { template, .. } => {
+                template.replace("{version}", version.original_value())
+            }
+        }
+    }
+}
+
+/// Replaces the version group of `pattern` with a `{version}` placeholder, which only works when
+/// everything else is literal text.
+fn reverse_template(pattern: &str) -> Option<String> {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    let pattern = match pattern.strip_suffix('$') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => pattern,
+    };
+    let start = pattern
+        .find("(?P<version>")
+        .or_else(|| pattern.find("(?<version>"))?;
+
+    let mut depth = 0;
+    let mut escaped = false;
+    let mut end = None;
+    for (index, c) in pattern[start..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    end = Some(start + index + 1);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(format!(
+        "{}{{version}}{}",
+        unescape(&pattern[..start])?,
+        unescape(&pattern[end?..])?
+    ))
+}
+
+/// Text matched by a regex made of literals only.
+fn unescape(pattern: &str) -> Option<String> {
+    let mut text = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                c if c.is_ascii_alphanumeric() => return None,
+                c => text.push(c),
+            },
+            '.' | '^' | '$' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' => {
+                return None
+            }
+            c => text.push(c),
+        }
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TagMapper;
+    use crate::config::TagMapping;
+
+    #[test]
+    fn test_tag_mapper() {
+        let identity = TagMapper::new(None).unwrap();
+        let version = identity.version("v1.2.3").unwrap();
+        assert_eq!(identity.tag(&version), "v1.2.3");
+        assert!(identity.version("cli/v1.2.3").is_none());
+
+        let prefix = TagMapper::new(Some(&TagMapping::Prefix("cli/".to_string()))).unwrap();
+        let version = prefix.version("cli/v1.2.3").unwrap();
+        assert_eq!(version.to_string(), "1.2.3");
+        assert_eq!(prefix.tag(&version), "cli/v1.2.3");
+        assert!(prefix.version("server/v1.2.3").is_none());
+
+        let regex = TagMapper::new(Some(&TagMapping::Regex(
+            r"^mytool\.release-(?P<version>[0-9]+(\.[0-9]+)*)$".to_string(),
+        )))
+        .unwrap();
+        let version = regex.version("mytool.release-1.2").unwrap();
+        assert_eq!(version.to_string(), "1.2.0");
+        assert_eq!(regex.tag(&version), "mytool.release-1.2");
+        assert!(regex.version("mytool.release-1.2-rc").is_none());
+
+        assert!(TagMapper::new(Some(&TagMapping::Regex(
+            "v?(?P<version>[0-9.]+)".to_string()
+        )))
+        .is_err());
+        assert!(TagMapper::new(Some(&TagMapping::Regex("release-.*".to_string()))).is_err());
+    }
+}
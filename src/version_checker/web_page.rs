@@ -0,0 +1,199 @@
+use async_trait::async_trait;
+use color_eyre::{eyre::WrapErr, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::Selector;
+use tracing::{debug, instrument, trace};
+use url::Url;
+
+use super::{file_template_regex, version_pattern, VersionCheck};
+use crate::config::WebPageConfig;
+use crate::version::LenientVersion;
+use crate::CLIENT;
+
+lazy_static! {
+    static ref LINKS_SELECTOR: Selector = Selector::parse("a[href]").unwrap();
+}
+
+/// Checker for plain HTTP index pages, like the directory listings of `ftp.gnu.org`.
+///
+/// Every link on the page is matched against a regex with a `version` capture group, which
+/// defaults to the file template of the current download url.
+#[derive(Debug)]
+pub struct WebPage {
+    page_url: Url,
+    pattern: Option<Regex>,
+    current_version: LenientVersion,
+    remote_version: Option<LenientVersion>,
+    remote_url: Option<String>,
+}
+
+impl WebPage {
+    pub fn new(
+        current_download_url: &Url,
+        current_version: LenientVersion,
+        config: &WebPageConfig,
+    ) -> Result<Self> {
+        let page_url = match &config.url {
+            Some(url) => url
+                .parse()
+                .wrap_err_with(|| format!("failed to parse page url {:?}", url))?,
+            None => current_download_url
+                .join(".")
+                .wrap_err("failed to get the download directory")?,
+        };
+        let pattern = config.pattern.as_deref().map(version_pattern).transpose()?;
+
+        Ok(Self {
+            page_url,
+            pattern,
+            current_version,
+            remote_version: None,
+            remote_url: None,
+        })
+    }
+
+    #[instrument]
+    async fn do_fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        let pattern = match &self.pattern {
+            Some(pattern) => pattern.clone(),
+            None => file_template_regex(file_template)?,
+        };
+        trace!(message = "fetching web page", url = %self.page_url, %pattern);
+        let body = CLIENT
+            .get(self.page_url.clone())
+            .send()
+            .await
+            .wrap_err("failed to load web page")?
+            .error_for_status()?
+            .text()
+            .await
+            .wrap_err("failed to get the content of web page")?;
+
+        let page = scraper::Html::parse_document(&body);
+        let page_url = &self.page_url;
+        let links = page
+            .select(&LINKS_SELECTOR)
+            .filter_map(|a| a.value().attr("href"))
+            .filter_map(|href| page_url.join(href).ok())
+            .collect::<Vec<_>>();
+
+        for link in links {
+            let file_name = match link.path_segments().and_then(|mut s| s.next_back()) {
+                Some(file_name) => file_name,
+                None => continue,
+            };
+            let version = match pattern
+                .captures(file_name)
+                .and_then(|c| c.name("version"))
+                .and_then(|v| LenientVersion::parse(v.as_str()).ok())
+            {
+                Some(version) if version.inner().pre.is_empty() => version,
+                _ => continue,
+            };
+            debug!("found version {} at {}", version, link);
+            if self.remote_version.as_ref().is_none_or(|l| &version > l) {
+                self.remote_version = Some(version);
+                self.remote_url = Some(link.to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VersionCheck for WebPage {
+    fn checker_name(&self) -> &'static str {
+        "web-page"
+    }
+
+    async fn fetch_last_version(&mut self, file_template: &str) -> Result<()> {
+        self.do_fetch_last_version(file_template).await
+    }
+
+    fn get_current_version(&self) -> &LenientVersion {
+        &self.current_version
+    }
+
+    fn get_remote_version(&self) -> Option<&LenientVersion> {
+        self.remote_version.as_ref()
+    }
+
+    fn get_download_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{VersionCheck, WebPage};
+    use crate::config::WebPageConfig;
+    use crate::version::LenientVersion;
+    use crate::{package::VERSION_PLACEHOLDER, setup_error_handlers};
+
+    const INDEX_PAGE: &str = r#"<html><body><pre>
+        <a href="../">Parent Directory</a>
+        <a href="hello-2.10.tar.gz">hello-2.10.tar.gz</a>
+        <a href="hello-2.12.1.tar.gz">hello-2.12.1.tar.gz</a>
+        <a href="hello-2.12.1.tar.gz.sig">hello-2.12.1.tar.gz.sig</a>
+        <a href="hello-2.12.tar.gz">hello-2.12.tar.gz</a>
+        <a href="/gnu/hello/hello-2.13-rc1.tar.gz">hello-2.13-rc1.tar.gz</a>
+    </pre></body></html>"#;
+
+    #[tokio::test]
+    async fn fetch_last_version() {
+        setup_error_handlers().ok();
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/gnu/hello/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(INDEX_PAGE))
+            .mount(&mock_server)
+            .await;
+
+        let current_url = format!("{}/gnu/hello/hello-2.10.tar.gz", mock_server.uri());
+        let mut web_page: Box<dyn VersionCheck> = Box::new(
+            WebPage::new(
+                &current_url.parse().unwrap(),
+                LenientVersion::parse("2.10").unwrap(),
+                &WebPageConfig::default(),
+            )
+            .unwrap(),
+        );
+        web_page
+            .fetch_last_version(&format!("hello-{}.tar.gz", VERSION_PLACEHOLDER))
+            .await
+            .unwrap();
+
+        assert!(web_page.has_newer_version());
+        assert_eq!(
+            web_page.get_remote_version().unwrap().original_value(),
+            "2.12.1"
+        );
+        assert_eq!(
+            web_page.get_download_url(),
+            Some(format!("{}/gnu/hello/hello-2.12.1.tar.gz", mock_server.uri()).as_str())
+        );
+    }
+
+    #[test]
+    fn pattern_requires_version_group() {
+        let config = WebPageConfig {
+            url: None,
+            pattern: Some(r"hello-\d+\.tar\.gz".to_string()),
+        };
+        assert!(WebPage::new(
+            &"https://ftp.gnu.org/gnu/hello/hello-2.10.tar.gz"
+                .parse()
+                .unwrap(),
+            LenientVersion::parse("2.10").unwrap(),
+            &config,
+        )
+        .is_err());
+    }
+}